@@ -135,7 +135,8 @@ export class StellarSwap {
 
   @ApiProperty({
     description: 'The status of the swap',
-    example: 'created',
+    example: 'active',
+    enum: ['active', 'withdrawn', 'refunded', 'cancelled', 'settled'],
   })
-  status: 'active' | 'withdrawn' | 'refunded';
+  status: 'active' | 'withdrawn' | 'refunded' | 'cancelled' | 'settled';
 }
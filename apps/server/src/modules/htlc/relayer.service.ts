@@ -33,6 +33,13 @@ export class RelayerService implements OnModuleInit {
     void startStellarMonitoring({
       created: (swap) => {
         this.logger.log(`Stellar HTLC created: ${swap.swapId}`);
+        // The relayer only completes plain HTLCs; options may pay the funds elsewhere
+        if (!swap.isPlain) {
+          this.logger.warn(
+            `Ignoring Stellar HTLC ${swap.swapId} created with swap options`,
+          );
+          return;
+        }
         this.intentService.patchStatus(swap.swapId, 'stellar_locked');
       },
       withdrawn: (swap) => {
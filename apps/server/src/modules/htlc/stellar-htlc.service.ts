@@ -105,7 +105,11 @@ export class StellarHtlcService {
       ? 'withdrawn'
       : swap.is_refunded
         ? 'refunded'
-        : 'active';
+        : swap.is_cancelled
+          ? 'cancelled'
+          : swap.is_settled
+            ? 'settled'
+            : 'active';
 
     return {
       swapId,
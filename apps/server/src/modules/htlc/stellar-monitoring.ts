@@ -10,8 +10,22 @@ interface On {
 }
 
 // interfaces for the events
+export interface SwapCreatedOptions {
+  payouts: { recipient: string; bps: number }[];
+  integrator: string | null;
+  integratorFeeBps: number;
+  callback: string | null;
+  premium: number;
+  resolverSender: boolean;
+  resolverClaims: boolean;
+  arbiter: string | null;
+  approvers: string[];
+  approvalThreshold: number;
+}
+
 export interface SwapCreatedEvent {
   type: 'created';
+  seq: number;
   swapId: string;
   sender: string;
   recipient: string;
@@ -19,10 +33,15 @@ export interface SwapCreatedEvent {
   amount: number;
   hashlock: string;
   timelock: number;
+  options: SwapCreatedOptions;
+  // False when any option can redirect, split or return the funds differently
+  // from a plain HTLC
+  isPlain: boolean;
 }
 
 export interface SwapWithdrawnEvent {
   type: 'withdrawn';
+  seq: number;
   swapId: string;
   sender: string;
   recipient: string;
@@ -33,6 +52,7 @@ export interface SwapWithdrawnEvent {
 
 export interface SwapRefundedEvent {
   type: 'refunded';
+  seq: number;
   swapId: string;
   sender: string;
   recipient: string;
@@ -70,62 +90,106 @@ export const startStellarMonitoring = async (on: On) => {
   }
 };
 
+// Event schema version published by the HTLC contract as the second topic
+const EVENT_VERSION = 3;
+
+// topics: [name, version, swap_id, hashlock]; value: struct payload
 function simpleEventProcessor(events: Api.EventResponse[], on: On): void {
   events.map((event) => {
     // eslint-disable-next-line @typescript-eslint/no-unsafe-return
-    const topics: string[] = event.topic.map((t) => scValToNative(t));
+    const topics: any[] = event.topic.map((t) => scValToNative(t));
     const value = scValToNative(event.value);
-    if (topics[0] === 'CREATED' && topics[1] === 'swap') {
-      const swapCreatedEvent = swapCreatedEventLog(value);
+    if (topics[1] !== EVENT_VERSION) {
+      return null;
+    }
+    if (topics[0] === 'CREATED') {
+      const swapCreatedEvent = swapCreatedEventLog(topics, value);
       on.created(swapCreatedEvent);
     }
-    if (topics[0] === 'WITHDRAWN' && topics[1] === 'withdraw') {
-      const swapWithdrawnEvent = swapWithdrawnEventLog(value);
+    if (topics[0] === 'WITHDRAWN') {
+      const swapWithdrawnEvent = swapWithdrawnEventLog(topics, value);
       on.withdrawn(swapWithdrawnEvent);
     }
-    if (topics[0] === 'REFUNDED' && topics[1] === 'refund') {
-      const swapRefundedEvent = swapRefundedEventLog(value);
+    if (topics[0] === 'REFUNDED') {
+      const swapRefundedEvent = swapRefundedEventLog(topics, value);
       on.refunded(swapRefundedEvent);
     }
     return null;
   });
 }
 
-function swapCreatedEventLog(obj: any[]): SwapCreatedEvent {
+// SwapOptions { payouts, integrator, integrator_fee_bps, callback, callback_data,
+//   premium, resolver_sender, resolver_claims, arbiter, approvers, approval_threshold }
+function swapCreatedOptions(obj: any): SwapCreatedOptions {
+  return {
+    payouts: obj.payouts ?? [],
+    integrator: obj.integrator ?? null,
+    integratorFeeBps: obj.integrator_fee_bps,
+    callback: obj.callback ?? null,
+    premium: obj.premium,
+    resolverSender: obj.resolver_sender,
+    resolverClaims: obj.resolver_claims,
+    arbiter: obj.arbiter ?? null,
+    approvers: obj.approvers ?? [],
+    approvalThreshold: obj.approval_threshold,
+  };
+}
+
+function isPlainSwap(options: SwapCreatedOptions): boolean {
+  return (
+    options.payouts.length === 0 &&
+    options.integrator === null &&
+    options.callback === null &&
+    BigInt(options.premium) === 0n &&
+    !options.resolverSender &&
+    !options.resolverClaims &&
+    options.arbiter === null &&
+    options.approvers.length === 0
+  );
+}
+
+// SwapCreated { seq, sender, recipient, token, amount, timelock, options }
+function swapCreatedEventLog(topics: any[], obj: any): SwapCreatedEvent {
+  const options = swapCreatedOptions(obj.options);
   return {
     type: 'created',
-    swapId: Buffer.from(obj[0]).toString('hex'),
-    sender: obj[1],
-    recipient: obj[2],
-    token: obj[3],
-    amount: obj[4],
-    hashlock: Buffer.from(obj[5]).toString('hex'),
-    timelock: obj[6],
+    seq: obj.seq,
+    swapId: Buffer.from(topics[2]).toString('hex'),
+    sender: obj.sender,
+    recipient: obj.recipient,
+    token: obj.token,
+    amount: obj.amount,
+    hashlock: Buffer.from(topics[3]).toString('hex'),
+    timelock: obj.timelock,
+    options,
+    isPlain: isPlainSwap(options),
   };
 }
 
-//(swap_id, updated_swap.sender, recipient, updated_swap.token, updated_swap.amount, preimage)
-function swapWithdrawnEventLog(obj: any[]): SwapWithdrawnEvent {
+// SwapWithdrawn { seq, sender, recipient, token, amount, preimage }
+function swapWithdrawnEventLog(topics: any[], obj: any): SwapWithdrawnEvent {
   return {
     type: 'withdrawn',
-    swapId: Buffer.from(obj[0]).toString('hex'),
-    sender: obj[1],
-    recipient: obj[2],
-    token: obj[3],
-    amount: obj[4],
-    preimage: Buffer.from(obj[5]).toString('hex'),
+    seq: obj.seq,
+    swapId: Buffer.from(topics[2]).toString('hex'),
+    sender: obj.sender,
+    recipient: obj.recipient,
+    token: obj.token,
+    amount: obj.amount,
+    preimage: Buffer.from(obj.preimage).toString('hex'),
   };
 }
 
-// swap_id, sender, updated_swap.recipient, updated_swap.token, updated_swap.amount)
-function swapRefundedEventLog(obj: any[]): SwapRefundedEvent {
+// SwapRefunded { seq, sender, recipient, token, amount }
+function swapRefundedEventLog(topics: any[], obj: any): SwapRefundedEvent {
   return {
     type: 'refunded',
-    swapId: Buffer.from(obj[0]).toString('hex'),
-    sender: obj[1],
-    recipient: obj[2],
-    token: obj[3],
-    amount: obj[4],
+    seq: obj.seq,
+    swapId: Buffer.from(topics[2]).toString('hex'),
+    sender: obj.sender,
+    recipient: obj.recipient,
+    token: obj.token,
+    amount: obj.amount,
   };
 }
 
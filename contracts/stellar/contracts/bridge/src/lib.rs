@@ -0,0 +1,317 @@
+#![no_std]
+
+//! Lock-and-mint bridge for a wrapped SEP-41 token.
+//!
+//! Deposits locked on an EVM chain are minted here once an M-of-N committee
+//! attests to them; burns here are released on the EVM side by the committee.
+//! The bridge must be the admin of the wrapped Stellar Asset Contract.
+
+use soroban_sdk::{
+    contract, contractimpl, contracttype, crypto::Hash, symbol_short, token, xdr::ToXdr, Address,
+    BytesN, Env, Symbol, Vec,
+};
+
+pub use committee::{Attestation, Signer, MAX_SIGNERS};
+
+const DAY_IN_LEDGERS: u32 = 17280;
+const PERSISTENT_TTL_THRESHOLD: u32 = 30 * DAY_IN_LEDGERS;
+const PERSISTENT_TTL_EXTEND_TO: u32 = 120 * DAY_IN_LEDGERS;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+// Event symbols
+pub const MINTED: Symbol = symbol_short!("MINTED");
+pub const BURNED: Symbol = symbol_short!("BURNED");
+pub const ROTATED: Symbol = symbol_short!("ROTATED");
+
+// Domain tags of the signed digests
+const MINT_TAG: Symbol = symbol_short!("mint");
+const ROTATE_TAG: Symbol = symbol_short!("rotate");
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    Token,
+    Signers,
+    Threshold,
+    Epoch,      // Incremented by every signer rotation
+    DailyLimit,
+    MintWindow, // Amount minted during the current UTC day
+    BurnNonce,
+    Processed(u64, BytesN<20>, BytesN<32>), // (chain_id, source, deposit_id) of a minted deposit
+}
+
+/// Deposit locked on the EVM chain, identified by its source and `deposit_id`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct Deposit {
+    pub chain_id: u64,
+    pub source: BytesN<20>,     // EVM bridge contract holding the deposit
+    pub deposit_id: BytesN<32>, // Unique per deposit on `source`
+    pub recipient: Address,
+    pub amount: i128,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct MintWindow {
+    pub day: u64, // Days since the Unix epoch
+    pub minted: i128,
+}
+
+/// Payload of a `MINTED` event; topics are `(MINTED, chain_id, deposit_id)`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct Minted {
+    pub recipient: Address,
+    pub amount: i128,
+    pub digest: BytesN<32>,
+}
+
+/// Payload of a `BURNED` event; topics are `(BURNED, from)`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct Burned {
+    pub nonce: u64,
+    pub amount: i128,
+    pub chain_id: u64,
+    pub evm_recipient: BytesN<20>,
+}
+
+/// Payload of a `ROTATED` event; topics are `(ROTATED, epoch)` with the new epoch
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct Rotated {
+    pub signers: Vec<Signer>,
+    pub threshold: u32,
+}
+
+fn bump_persistent(env: &Env, key: &DataKey) {
+    env.storage()
+        .persistent()
+        .extend_ttl(key, PERSISTENT_TTL_THRESHOLD, PERSISTENT_TTL_EXTEND_TO);
+}
+
+fn require_admin(env: &Env) {
+    let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("admin not set");
+    admin.require_auth();
+}
+
+fn signers(env: &Env) -> Vec<Signer> {
+    env.storage().instance().get(&DataKey::Signers).expect("signers not set")
+}
+
+fn threshold(env: &Env) -> u32 {
+    env.storage().instance().get(&DataKey::Threshold).expect("threshold not set")
+}
+
+fn epoch(env: &Env) -> u32 {
+    env.storage().instance().get(&DataKey::Epoch).unwrap_or(0)
+}
+
+fn set_committee(env: &Env, signers: &Vec<Signer>, threshold: u32) {
+    committee::validate(signers, threshold);
+    env.storage().instance().set(&DataKey::Signers, signers);
+    env.storage().instance().set(&DataKey::Threshold, &threshold);
+}
+
+fn store_daily_limit(env: &Env, limit: i128) {
+    if limit < 0 {
+        panic!("daily limit cannot be negative");
+    }
+    env.storage().instance().set(&DataKey::DailyLimit, &limit);
+}
+
+fn processed_key(deposit: &Deposit) -> DataKey {
+    DataKey::Processed(deposit.chain_id, deposit.source.clone(), deposit.deposit_id.clone())
+}
+
+fn deposit_digest(env: &Env, deposit: &Deposit) -> Hash<32> {
+    let message = (MINT_TAG, env.current_contract_address(), deposit.clone());
+    env.crypto().keccak256(&message.to_xdr(env))
+}
+
+fn rotation_digest(env: &Env, signers: &Vec<Signer>, threshold: u32) -> Hash<32> {
+    let message = (ROTATE_TAG, env.current_contract_address(), epoch(env), signers.clone(), threshold);
+    env.crypto().keccak256(&message.to_xdr(env))
+}
+
+fn mint_window(env: &Env) -> MintWindow {
+    let day = env.ledger().timestamp() / SECONDS_PER_DAY;
+    let window: Option<MintWindow> = env.storage().instance().get(&DataKey::MintWindow);
+    match window {
+        Some(window) if window.day == day => window,
+        _ => MintWindow { day, minted: 0 },
+    }
+}
+
+#[contract]
+pub struct BridgeContract;
+
+#[contractimpl]
+impl BridgeContract {
+    /// Initialize the bridge
+    ///
+    /// # Arguments
+    /// * `admin` - Address allowed to change the daily limit
+    /// * `token` - Wrapped Stellar Asset Contract administered by this contract
+    /// * `signers` - Initial committee
+    /// * `threshold` - Number of attestations required (M of N)
+    /// * `daily_limit` - Maximum amount minted per UTC day
+    pub fn __constructor(
+        env: Env,
+        admin: Address,
+        token: Address,
+        signers: Vec<Signer>,
+        threshold: u32,
+        daily_limit: i128,
+    ) {
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Token, &token);
+        set_committee(&env, &signers, threshold);
+        store_daily_limit(&env, daily_limit);
+    }
+
+    /// Mint wrapped tokens for an EVM deposit attested by the committee
+    ///
+    /// # Arguments
+    /// * `deposit` - The attested deposit
+    /// * `attestations` - Signatures over `get_deposit_digest(deposit)`, ordered by signer index
+    pub fn mint(env: Env, deposit: Deposit, attestations: Vec<Attestation>) {
+        if deposit.amount <= 0 {
+            panic!("amount must be positive");
+        }
+
+        // Keyed on the deposit's identity alone, so no other attested amount or
+        // recipient for the same deposit can mint again
+        let processed_key = processed_key(&deposit);
+        if env.storage().persistent().has(&processed_key) {
+            panic!("deposit already processed");
+        }
+        let digest = deposit_digest(&env, &deposit);
+        committee::verify(&env, &signers(&env), threshold(&env), &digest, &attestations);
+
+        let mut window = mint_window(&env);
+        window.minted += deposit.amount;
+        if window.minted > Self::get_daily_limit(env.clone()) {
+            panic!("daily mint limit exceeded");
+        }
+        env.storage().instance().set(&DataKey::MintWindow, &window);
+
+        env.storage().persistent().set(&processed_key, &true);
+        bump_persistent(&env, &processed_key);
+
+        let token_address: Address = env.storage().instance().get(&DataKey::Token).unwrap();
+        token::StellarAssetClient::new(&env, &token_address).mint(&deposit.recipient, &deposit.amount);
+
+        env.events().publish(
+            (MINTED, deposit.chain_id, deposit.deposit_id),
+            Minted {
+                recipient: deposit.recipient,
+                amount: deposit.amount,
+                digest: digest.to_bytes(),
+            },
+        );
+    }
+
+    /// Burn wrapped tokens to release the underlying asset on the EVM chain
+    ///
+    /// # Arguments
+    /// * `from` - Holder of the wrapped tokens
+    /// * `amount` - Amount to burn
+    /// * `chain_id` - EVM chain to release on
+    /// * `evm_recipient` - Address receiving the released asset
+    ///
+    /// Returns the burn nonce the committee uses to deduplicate releases.
+    pub fn burn(env: Env, from: Address, amount: i128, chain_id: u64, evm_recipient: BytesN<20>) -> u64 {
+        from.require_auth();
+        if amount <= 0 {
+            panic!("amount must be positive");
+        }
+
+        let token_address: Address = env.storage().instance().get(&DataKey::Token).unwrap();
+        token::Client::new(&env, &token_address).burn(&from, &amount);
+
+        let nonce: u64 = env.storage().instance().get(&DataKey::BurnNonce).unwrap_or(0);
+        env.storage().instance().set(&DataKey::BurnNonce, &(nonce + 1));
+
+        env.events().publish(
+            (BURNED, from),
+            Burned { nonce, amount, chain_id, evm_recipient },
+        );
+        nonce
+    }
+
+    /// Replace the committee with one approved by the current committee
+    ///
+    /// # Arguments
+    /// * `new_signers` - Next committee
+    /// * `new_threshold` - Next threshold
+    /// * `attestations` - Current committee's signatures over `get_rotation_digest`
+    pub fn rotate_signers(env: Env, new_signers: Vec<Signer>, new_threshold: u32, attestations: Vec<Attestation>) {
+        let digest = rotation_digest(&env, &new_signers, new_threshold);
+        committee::verify(&env, &signers(&env), threshold(&env), &digest, &attestations);
+
+        set_committee(&env, &new_signers, new_threshold);
+        let new_epoch = epoch(&env) + 1;
+        env.storage().instance().set(&DataKey::Epoch, &new_epoch);
+
+        env.events().publish(
+            (ROTATED, new_epoch),
+            Rotated { signers: new_signers, threshold: new_threshold },
+        );
+    }
+
+    /// Digest the committee signs to attest a deposit
+    pub fn get_deposit_digest(env: Env, deposit: Deposit) -> BytesN<32> {
+        deposit_digest(&env, &deposit).to_bytes()
+    }
+
+    /// Digest the current committee signs to approve a rotation
+    pub fn get_rotation_digest(env: Env, new_signers: Vec<Signer>, new_threshold: u32) -> BytesN<32> {
+        rotation_digest(&env, &new_signers, new_threshold).to_bytes()
+    }
+
+    /// Check whether a deposit with the same chain, source and id has already been minted
+    pub fn is_processed(env: Env, deposit: Deposit) -> bool {
+        env.storage().persistent().has(&processed_key(&deposit))
+    }
+
+    pub fn get_signers(env: Env) -> Vec<Signer> {
+        signers(&env)
+    }
+
+    pub fn get_threshold(env: Env) -> u32 {
+        threshold(&env)
+    }
+
+    /// Get the number of rotations so far
+    pub fn get_epoch(env: Env) -> u32 {
+        epoch(&env)
+    }
+
+    pub fn get_token(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Token).unwrap()
+    }
+
+    pub fn get_daily_limit(env: Env) -> i128 {
+        env.storage().instance().get(&DataKey::DailyLimit).unwrap_or(0)
+    }
+
+    /// Get the amount minted so far during the current UTC day
+    pub fn get_minted_today(env: Env) -> i128 {
+        mint_window(&env).minted
+    }
+
+    /// Set the maximum amount minted per UTC day
+    ///
+    /// # Arguments
+    /// * `limit` - New limit
+    pub fn set_daily_limit(env: Env, limit: i128) {
+        require_admin(&env);
+        store_daily_limit(&env, limit);
+    }
+}
+
+mod test;
@@ -0,0 +1,291 @@
+#![cfg(test)]
+extern crate std;
+
+use super::*;
+use ed25519_dalek::Signer as _;
+use soroban_sdk::{
+    testutils::{Address as _, Events, Ledger},
+    token::{Client as TokenClient, StellarAssetClient},
+    vec, Address, BytesN, Env, FromVal, IntoVal,
+};
+
+/// Committee member with a locally generated key
+enum TestSigner {
+    Ed25519(ed25519_dalek::SigningKey),
+    Secp256k1(k256::ecdsa::SigningKey),
+}
+
+impl TestSigner {
+    fn ed25519(seed: u8) -> Self {
+        TestSigner::Ed25519(ed25519_dalek::SigningKey::from_bytes(&[seed; 32]))
+    }
+
+    fn secp256k1(seed: u8) -> Self {
+        TestSigner::Secp256k1(k256::ecdsa::SigningKey::from_bytes(&[seed; 32].into()).unwrap())
+    }
+
+    fn public_key(&self, env: &Env) -> Signer {
+        match self {
+            TestSigner::Ed25519(key) => {
+                Signer::Ed25519(BytesN::from_array(env, &key.verifying_key().to_bytes()))
+            }
+            TestSigner::Secp256k1(key) => {
+                let point = key.verifying_key().to_encoded_point(false);
+                Signer::Secp256k1(BytesN::from_array(env, point.as_bytes().try_into().unwrap()))
+            }
+        }
+    }
+
+    fn attest(&self, env: &Env, index: u32, digest: &BytesN<32>) -> Attestation {
+        let digest = digest.to_array();
+        let (signature, recovery_id) = match self {
+            TestSigner::Ed25519(key) => (key.sign(&digest).to_bytes(), 0),
+            TestSigner::Secp256k1(key) => {
+                let (signature, recovery_id) = key.sign_prehash_recoverable(&digest).unwrap();
+                (signature.to_bytes().into(), recovery_id.to_byte() as u32)
+            }
+        };
+        Attestation {
+            signer: index,
+            signature: BytesN::from_array(env, &signature),
+            recovery_id,
+        }
+    }
+}
+
+fn committee(env: &Env, members: &[TestSigner]) -> Vec<Signer> {
+    let mut signers = Vec::new(env);
+    for member in members {
+        signers.push_back(member.public_key(env));
+    }
+    signers
+}
+
+struct BridgeTest<'a> {
+    env: Env,
+    admin: Address,
+    user: Address,
+    token: TokenClient<'a>,
+    bridge: BridgeContractClient<'a>,
+    members: [TestSigner; 3],
+}
+
+impl<'a> BridgeTest<'a> {
+    /// 2-of-3 committee mixing both key types, with a 1000 daily limit
+    fn setup() -> Self {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().with_mut(|li| {
+            li.timestamp = 12345;
+        });
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        let sac = env.register_stellar_asset_contract_v2(admin.clone());
+        let members = [TestSigner::ed25519(1), TestSigner::ed25519(2), TestSigner::secp256k1(3)];
+
+        let bridge_address = env.register(
+            BridgeContract,
+            (admin.clone(), sac.address(), committee(&env, &members), 2u32, 1000i128),
+        );
+        StellarAssetClient::new(&env, &sac.address()).set_admin(&bridge_address);
+
+        BridgeTest {
+            token: TokenClient::new(&env, &sac.address()),
+            bridge: BridgeContractClient::new(&env, &bridge_address),
+            env,
+            admin,
+            user,
+            members,
+        }
+    }
+
+    fn deposit(&self, id: u8, amount: i128) -> Deposit {
+        Deposit {
+            chain_id: 11155111,
+            source: BytesN::from_array(&self.env, &[0xaa; 20]),
+            deposit_id: BytesN::from_array(&self.env, &[id; 32]),
+            recipient: self.user.clone(),
+            amount,
+        }
+    }
+
+    fn attest_deposit(&self, deposit: &Deposit, indexes: &[u32]) -> Vec<Attestation> {
+        let digest = self.bridge.get_deposit_digest(deposit);
+        let mut attestations = Vec::new(&self.env);
+        for &index in indexes {
+            attestations.push_back(self.members[index as usize].attest(&self.env, index, &digest));
+        }
+        attestations
+    }
+}
+
+#[test]
+fn test_mint_with_threshold() {
+    let test = BridgeTest::setup();
+    let deposit = test.deposit(1, 400);
+
+    // One ed25519 and one secp256k1 signature
+    test.bridge.mint(&deposit, &test.attest_deposit(&deposit, &[0, 2]));
+
+    let events = test.env.events().all();
+    let (contract, topics, data) = events.last().unwrap();
+    assert_eq!(contract, test.bridge.address);
+    assert_eq!(topics, (MINTED, deposit.chain_id, deposit.deposit_id.clone()).into_val(&test.env));
+    assert_eq!(Minted::from_val(&test.env, &data).amount, 400);
+
+    assert_eq!(test.token.balance(&test.user), 400);
+    assert!(test.bridge.is_processed(&deposit));
+    assert_eq!(test.bridge.get_minted_today(), 400);
+}
+
+#[test]
+#[should_panic(expected = "deposit already processed")]
+fn test_mint_replay() {
+    let test = BridgeTest::setup();
+    let deposit = test.deposit(1, 400);
+    let attestations = test.attest_deposit(&deposit, &[0, 1]);
+
+    test.bridge.mint(&deposit, &attestations);
+    test.bridge.mint(&deposit, &attestations);
+}
+
+#[test]
+#[should_panic(expected = "deposit already processed")]
+fn test_mint_same_deposit_with_other_amount() {
+    let test = BridgeTest::setup();
+    let deposit = test.deposit(1, 400);
+    test.bridge.mint(&deposit, &test.attest_deposit(&deposit, &[0, 1]));
+
+    // Even a validly attested message can't mint the same deposit twice
+    let conflicting = test.deposit(1, 500);
+    assert!(test.bridge.is_processed(&conflicting));
+    test.bridge.mint(&conflicting, &test.attest_deposit(&conflicting, &[0, 1]));
+}
+
+#[test]
+#[should_panic(expected = "not enough attestations")]
+fn test_mint_below_threshold() {
+    let test = BridgeTest::setup();
+    let deposit = test.deposit(1, 400);
+
+    test.bridge.mint(&deposit, &test.attest_deposit(&deposit, &[2]));
+}
+
+#[test]
+#[should_panic(expected = "attestations not ordered by signer")]
+fn test_mint_duplicate_signer() {
+    let test = BridgeTest::setup();
+    let deposit = test.deposit(1, 400);
+
+    test.bridge.mint(&deposit, &test.attest_deposit(&deposit, &[1, 1]));
+}
+
+#[test]
+fn test_mint_rejects_signatures_over_other_deposit() {
+    let test = BridgeTest::setup();
+    let deposit = test.deposit(1, 400);
+    let inflated = test.deposit(1, 900);
+
+    let ed25519_only = test.attest_deposit(&deposit, &[0, 1]);
+    assert!(test.bridge.try_mint(&inflated, &ed25519_only).is_err());
+
+    let with_secp256k1 = vec![
+        &test.env,
+        test.attest_deposit(&inflated, &[0]).get_unchecked(0),
+        test.attest_deposit(&deposit, &[2]).get_unchecked(0),
+    ];
+    assert!(test.bridge.try_mint(&inflated, &with_secp256k1).is_err());
+    assert_eq!(test.token.balance(&test.user), 0);
+}
+
+#[test]
+fn test_daily_mint_limit() {
+    let test = BridgeTest::setup();
+    let first = test.deposit(1, 700);
+    let second = test.deposit(2, 400);
+
+    test.bridge.mint(&first, &test.attest_deposit(&first, &[0, 1]));
+    let attestations = test.attest_deposit(&second, &[0, 1]);
+    assert!(test.bridge.try_mint(&second, &attestations).is_err());
+
+    // The limit resets with the next UTC day
+    test.env.ledger().with_mut(|li| {
+        li.timestamp += 24 * 60 * 60;
+    });
+    test.bridge.mint(&second, &attestations);
+    assert_eq!(test.bridge.get_minted_today(), 400);
+    assert_eq!(test.token.balance(&test.user), 1100);
+
+    test.bridge.set_daily_limit(&5000);
+    assert_eq!(test.env.auths()[0].0, test.admin);
+    assert_eq!(test.bridge.get_daily_limit(), 5000);
+}
+
+#[test]
+fn test_rotate_signers() {
+    let test = BridgeTest::setup();
+    let next_members = [TestSigner::secp256k1(4), TestSigner::ed25519(5)];
+    let next_signers = committee(&test.env, &next_members);
+
+    let digest = test.bridge.get_rotation_digest(&next_signers, &1);
+    let approvals = vec![
+        &test.env,
+        test.members[1].attest(&test.env, 1, &digest),
+        test.members[2].attest(&test.env, 2, &digest),
+    ];
+    test.bridge.rotate_signers(&next_signers, &1, &approvals);
+
+    assert_eq!(test.bridge.get_signers(), next_signers);
+    assert_eq!(test.bridge.get_threshold(), 1);
+    assert_eq!(test.bridge.get_epoch(), 1);
+
+    // Approvals are bound to the epoch, so they can't be replayed
+    assert!(test.bridge.try_rotate_signers(&next_signers, &1, &approvals).is_err());
+
+    // The old committee can no longer mint; the new one can
+    let deposit = test.deposit(1, 400);
+    let old_attestations = test.attest_deposit(&deposit, &[0, 1]);
+    assert!(test.bridge.try_mint(&deposit, &old_attestations).is_err());
+
+    let digest = test.bridge.get_deposit_digest(&deposit);
+    test.bridge.mint(&deposit, &vec![&test.env, next_members[0].attest(&test.env, 0, &digest)]);
+    assert_eq!(test.token.balance(&test.user), 400);
+}
+
+#[test]
+#[should_panic(expected = "invalid threshold")]
+fn test_rotate_to_invalid_threshold() {
+    let test = BridgeTest::setup();
+    let next_signers = committee(&test.env, &[TestSigner::ed25519(5)]);
+
+    let digest = test.bridge.get_rotation_digest(&next_signers, &2);
+    let approvals = vec![
+        &test.env,
+        test.members[0].attest(&test.env, 0, &digest),
+        test.members[1].attest(&test.env, 1, &digest),
+    ];
+    test.bridge.rotate_signers(&next_signers, &2, &approvals);
+}
+
+#[test]
+fn test_burn() {
+    let test = BridgeTest::setup();
+    let deposit = test.deposit(1, 400);
+    let evm_recipient = BytesN::from_array(&test.env, &[0xbb; 20]);
+    test.bridge.mint(&deposit, &test.attest_deposit(&deposit, &[0, 1]));
+
+    assert_eq!(test.bridge.burn(&test.user, &150, &11155111, &evm_recipient), 0);
+    assert_eq!(test.env.auths()[0].0, test.user);
+
+    let events = test.env.events().all();
+    let (_, topics, data) = events.last().unwrap();
+    assert_eq!(topics, (BURNED, test.user.clone()).into_val(&test.env));
+    assert_eq!(
+        Burned::from_val(&test.env, &data),
+        Burned { nonce: 0, amount: 150, chain_id: 11155111, evm_recipient: evm_recipient.clone() }
+    );
+
+    assert_eq!(test.bridge.burn(&test.user, &50, &11155111, &evm_recipient), 1);
+    assert_eq!(test.token.balance(&test.user), 200);
+}
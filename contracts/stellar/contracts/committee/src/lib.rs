@@ -0,0 +1,78 @@
+#![no_std]
+
+//! M-of-N committee attestations shared by the contracts: signer keys, their
+//! signatures and verification of both ed25519 and secp256k1 signers.
+
+use soroban_sdk::{contracttype, crypto::Hash, BytesN, Env, Vec};
+
+/// Maximum number of committee members
+pub const MAX_SIGNERS: u32 = 20;
+
+/// Public key of a committee member
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub enum Signer {
+    Ed25519(BytesN<32>),
+    Secp256k1(BytesN<65>), // Uncompressed SEC-1 key
+}
+
+/// One committee member's signature over a message digest
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct Attestation {
+    pub signer: u32, // Index in the signer set
+    pub signature: BytesN<64>,
+    pub recovery_id: u32, // Only used by secp256k1 signers
+}
+
+pub fn validate(signers: &Vec<Signer>, threshold: u32) {
+    if signers.is_empty() {
+        panic!("signer set is empty");
+    }
+    if signers.len() > MAX_SIGNERS {
+        panic!("too many signers");
+    }
+    if threshold == 0 || threshold > signers.len() {
+        panic!("invalid threshold");
+    }
+    for i in 0..signers.len() {
+        for j in (i + 1)..signers.len() {
+            if signers.get_unchecked(i) == signers.get_unchecked(j) {
+                panic!("duplicate signer");
+            }
+        }
+    }
+}
+
+/// Check that at least `threshold` distinct members of `signers` signed `digest`.
+/// Attestations must be ordered by strictly increasing signer index.
+pub fn verify(env: &Env, signers: &Vec<Signer>, threshold: u32, digest: &Hash<32>, attestations: &Vec<Attestation>) {
+    if attestations.len() < threshold {
+        panic!("not enough attestations");
+    }
+
+    let message = digest.to_bytes().into();
+    let mut next_index = 0;
+    for attestation in attestations.iter() {
+        if attestation.signer < next_index {
+            panic!("attestations not ordered by signer");
+        }
+        next_index = attestation.signer + 1;
+
+        match signers.get(attestation.signer).expect("unknown signer") {
+            Signer::Ed25519(public_key) => {
+                env.crypto().ed25519_verify(&public_key, &message, &attestation.signature);
+            }
+            Signer::Secp256k1(public_key) => {
+                let recovered = env.crypto().secp256k1_recover(
+                    digest,
+                    &attestation.signature,
+                    attestation.recovery_id,
+                );
+                if recovered != public_key {
+                    panic!("invalid signature");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use soroban_sdk::{Address, Bytes, Env, Vec};
+
+use crate::events::{self, SwapApproved};
+use crate::{bump_persistent, DataKey, HTLCSwap};
+
+/// Maximum size of a swap's approver set
+pub const MAX_APPROVERS: u32 = 10;
+
+pub fn validate(approvers: &Vec<Address>, threshold: u32) {
+    if approvers.is_empty() {
+        if threshold != 0 {
+            panic!("approval threshold set without approvers");
+        }
+        return;
+    }
+    if approvers.len() > MAX_APPROVERS {
+        panic!("too many approvers");
+    }
+    if threshold == 0 || threshold > approvers.len() {
+        panic!("invalid approval threshold");
+    }
+    for (i, approver) in approvers.iter().enumerate() {
+        if approvers.first_index_of(&approver) != Some(i as u32) {
+            panic!("duplicate approver");
+        }
+    }
+}
+
+pub fn get(env: &Env, swap_id: &Bytes) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Approvals(swap_id.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+fn ensure_approver(swap: &HTLCSwap, approver: &Address) {
+    if !swap.approvers.contains(approver) {
+        panic!("not an approver");
+    }
+}
+
+/// Record an approver's consent to release a swap; approving twice is a no-op
+pub fn approve(env: &Env, swap_id: &Bytes, swap: &HTLCSwap, approver: &Address) {
+    ensure_approver(swap, approver);
+
+    let mut approvals = get(env, swap_id);
+    if approvals.contains(approver) {
+        return;
+    }
+    approvals.push_back(approver.clone());
+
+    let key = DataKey::Approvals(swap_id.clone());
+    env.storage().persistent().set(&key, &approvals);
+    bump_persistent(env, &key);
+
+    events::swap_approved(
+        env,
+        swap_id,
+        &swap.hashlock,
+        SwapApproved {
+            seq: events::next_seq(env),
+            approver: approver.clone(),
+            approvals: approvals.len(),
+        },
+    );
+}
+
+fn ensure_threshold(swap: &HTLCSwap, mut approvals: Vec<Address>, signers: &Vec<Address>) {
+    for signer in signers.iter() {
+        ensure_approver(swap, &signer);
+        if !approvals.contains(&signer) {
+            approvals.push_back(signer);
+        }
+    }
+    if approvals.len() < swap.approval_threshold {
+        panic!("not enough approvals");
+    }
+}
+
+/// Check that the recorded approvals plus `signers`, who authorized the current
+/// call, reach the swap's threshold
+pub fn ensure_approved(env: &Env, swap_id: &Bytes, swap: &HTLCSwap, signers: &Vec<Address>) {
+    ensure_threshold(swap, get(env, swap_id), signers);
+}
+
+/// Check that `signers`, who authorized the current call, reach the swap's
+/// threshold on their own. Recorded approvals only consent to a release.
+pub fn ensure_signed(env: &Env, swap: &HTLCSwap, signers: &Vec<Address>) {
+    ensure_threshold(swap, Vec::new(env), signers);
+}
+
+/// Drop the recorded approvals of a swap that closed
+pub fn clear(env: &Env, swap_id: &Bytes) {
+    env.storage().persistent().remove(&DataKey::Approvals(swap_id.clone()));
+}
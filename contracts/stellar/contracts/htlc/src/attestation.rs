@@ -0,0 +1,157 @@
+use committee::{Attestation, Signer};
+use soroban_sdk::{
+    contracttype, crypto::Hash, symbol_short, token, xdr::ToXdr, Address, Bytes, BytesN, Env, Symbol, Vec,
+    U256,
+};
+
+use crate::{bump_persistent, DataKey, HTLCSwap};
+
+// Domain tag of the signed funding digest
+const FUNDED_TAG: Symbol = symbol_short!("funded");
+
+/// Fields of the EVM HTLC's `Funded` event for the counterpart swap
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct EvmFunding {
+    pub chain_id: u64,
+    pub contract: BytesN<20>,
+    pub swap_id: BytesN<32>,
+    pub hashlock: BytesN<32>,
+    pub amount: U256,
+    pub timelock: u64,
+}
+
+/// Stellar token mirroring the asset held by an EVM HTLC contract
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct FundingAsset {
+    pub token: Address,
+    pub evm_decimals: u32, // Decimals of the EVM token, to compare amounts with the Stellar token's
+}
+
+/// Signers whose attestations `create_attested_swap` requires, M of N
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct Committee {
+    pub signers: Vec<Signer>,
+    pub threshold: u32,
+}
+
+pub fn validate(committee: &Committee) {
+    committee::validate(&committee.signers, committee.threshold);
+}
+
+pub fn committee(env: &Env) -> Option<Committee> {
+    env.storage().instance().get(&DataKey::AttestationCommittee)
+}
+
+pub fn funding_asset(env: &Env, chain_id: u64, contract: &BytesN<20>) -> Option<FundingAsset> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::FundingAsset(chain_id, contract.clone()))
+}
+
+pub fn set_funding_asset(env: &Env, chain_id: u64, contract: &BytesN<20>, asset: &FundingAsset) {
+    let key = DataKey::FundingAsset(chain_id, contract.clone());
+    env.storage().persistent().set(&key, asset);
+    bump_persistent(env, &key);
+}
+
+/// Check that the EVM funding is in the asset registered for its contract and
+/// covers `amount` of `token` once both are scaled to the same decimals
+fn ensure_covered(env: &Env, funding: &EvmFunding, token: &Address, amount: i128) {
+    let asset = funding_asset(env, funding.chain_id, &funding.contract).expect("EVM contract not registered");
+    if asset.token != *token {
+        panic!("token does not match EVM asset");
+    }
+
+    let decimals = token::Client::new(env, token).decimals();
+    let ten = U256::from_u32(env, 10);
+    let (evm_amount, stellar_amount) = if asset.evm_decimals >= decimals {
+        let scale = ten.pow(asset.evm_decimals - decimals);
+        (funding.amount.clone(), U256::from_u128(env, amount as u128).mul(&scale))
+    } else {
+        let scale = ten.pow(decimals - asset.evm_decimals);
+        (funding.amount.mul(&scale), U256::from_u128(env, amount as u128))
+    };
+    if evm_amount < stellar_amount {
+        panic!("EVM funding does not cover amount");
+    }
+}
+
+/// Identifier of an EVM funding, recording that it backs a swap
+fn funding_id(env: &Env, funding: &EvmFunding) -> BytesN<32> {
+    let message = (FUNDED_TAG, env.current_contract_address(), funding.clone());
+    env.crypto().keccak256(&message.to_xdr(env)).to_bytes()
+}
+
+/// Digest the committee signs: the EVM funding together with the Stellar swap it
+/// backs, so the attestations can't open a swap for anyone else
+pub fn funding_digest(
+    env: &Env,
+    funding: &EvmFunding,
+    swap_id: &Bytes,
+    sender: &Address,
+    recipient: &Address,
+) -> Hash<32> {
+    let message = (
+        FUNDED_TAG,
+        env.current_contract_address(),
+        funding.clone(),
+        swap_id.clone(),
+        sender.clone(),
+        recipient.clone(),
+    );
+    env.crypto().keccak256(&message.to_xdr(env))
+}
+
+/// Check that the Stellar swap mirrors the attested EVM funding, that enough
+/// committee members signed it for this swap and that it wasn't used for another
+pub fn verify_funding(
+    env: &Env,
+    funding: &EvmFunding,
+    attestations: &Vec<Attestation>,
+    swap_id: &Bytes,
+    sender: &Address,
+    recipient: &Address,
+    token: &Address,
+    amount: i128,
+    hashlock: &Bytes,
+    timelock: u64,
+) {
+    let committee = committee(env).expect("attestation committee not set");
+    if Bytes::from(funding.hashlock.clone()) != *hashlock {
+        panic!("hashlock does not match EVM swap");
+    }
+    // The counterpart must stay claimable after this swap's preimage is revealed
+    if timelock >= funding.timelock {
+        panic!("timelock must end before the EVM timelock");
+    }
+    if amount <= 0 {
+        panic!("amount must be positive");
+    }
+    ensure_covered(env, funding, token, amount);
+
+    let used_key = DataKey::AttestedFunding(funding_id(env, funding));
+    if env.storage().persistent().has(&used_key) {
+        panic!("EVM funding already used");
+    }
+    let digest = funding_digest(env, funding, swap_id, sender, recipient);
+    committee::verify(env, &committee.signers, committee.threshold, &digest, attestations);
+
+    env.storage().persistent().set(&used_key, &true);
+    bump_persistent(env, &used_key);
+}
+
+/// Check that a swap changed after creation is still backed by its attested EVM
+/// funding, if it has one
+pub fn ensure_still_backed(env: &Env, swap_id: &Bytes, swap: &HTLCSwap) {
+    let funding: Option<EvmFunding> = env.storage().persistent().get(&DataKey::SwapFunding(swap_id.clone()));
+    let Some(funding) = funding else {
+        return;
+    };
+    if swap.timelock >= funding.timelock {
+        panic!("timelock must end before the EVM timelock");
+    }
+    ensure_covered(env, &funding, &swap.token, swap.amount);
+}
@@ -0,0 +1,36 @@
+use soroban_sdk::{contractclient, token, Address, Bytes, Env};
+
+use crate::HTLCSwap;
+
+/// Interface a contract implements to receive withdrawn funds via a swap callback
+#[contractclient(name = "WithdrawReceiverClient")]
+pub trait WithdrawReceiver {
+    /// Called right after the contract transferred `amount` of `token` to the receiver.
+    /// Panicking reverts the whole withdraw.
+    fn on_htlc_withdraw(env: Env, swap_id: Bytes, token: Address, amount: i128, data: Bytes);
+}
+
+pub fn validate(callback: &Option<Address>, data: &Bytes, has_payouts: bool) {
+    if callback.is_none() {
+        if !data.is_empty() {
+            panic!("callback data set without callback");
+        }
+        return;
+    }
+    if has_payouts {
+        panic!("callback cannot be combined with payouts");
+    }
+}
+
+/// Transfer `amount` to the swap's callback target and notify it
+pub fn pay_and_call(env: &Env, swap_id: &Bytes, swap: &HTLCSwap, target: &Address, amount: i128) {
+    let token_client = token::Client::new(env, &swap.token);
+    token_client.transfer(&env.current_contract_address(), target, &amount);
+
+    WithdrawReceiverClient::new(env, target).on_htlc_withdraw(
+        swap_id,
+        &swap.token,
+        &amount,
+        &swap.callback_data,
+    );
+}
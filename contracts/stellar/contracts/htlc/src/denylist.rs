@@ -0,0 +1,55 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::events::{self, DenylistChanged};
+use crate::{bump_persistent, DataKey, Payout};
+
+pub fn is_denied(env: &Env, account: &Address) -> bool {
+    env.storage().persistent().has(&DataKey::Denied(account.clone()))
+}
+
+pub fn ensure_allowed(env: &Env, account: &Address) {
+    if is_denied(env, account) {
+        panic!("address denied");
+    }
+}
+
+/// Check every address a withdraw pays: the recipient or its payout split, the
+/// integrator and the callback target
+pub fn ensure_payees_allowed(
+    env: &Env,
+    recipient: &Address,
+    payouts: &Vec<Payout>,
+    integrator: &Option<Address>,
+    callback: &Option<Address>,
+) {
+    ensure_allowed(env, recipient);
+    for payout in payouts.iter() {
+        ensure_allowed(env, &payout.recipient);
+    }
+    for payee in [integrator, callback].into_iter().flatten() {
+        ensure_allowed(env, payee);
+    }
+}
+
+/// Add an address to the denylist; does nothing if it is already listed
+pub fn deny(env: &Env, account: &Address) {
+    let key = DataKey::Denied(account.clone());
+    if env.storage().persistent().has(&key) {
+        return;
+    }
+    env.storage().persistent().set(&key, &true);
+    bump_persistent(env, &key);
+
+    events::address_denied(env, account, DenylistChanged { seq: events::next_seq(env) });
+}
+
+/// Remove an address from the denylist; does nothing if it isn't listed
+pub fn allow(env: &Env, account: &Address) {
+    let key = DataKey::Denied(account.clone());
+    if !env.storage().persistent().has(&key) {
+        return;
+    }
+    env.storage().persistent().remove(&key);
+
+    events::address_allowed(env, account, DenylistChanged { seq: events::next_seq(env) });
+}
@@ -0,0 +1,50 @@
+use soroban_sdk::{token, Address, Bytes, Env};
+
+use crate::events::{self, SwapSettled};
+use crate::{close_swap, denylist, payout, HTLCSwap};
+
+pub fn validate(arbiter: &Option<Address>, sender: &Address, recipient: &Address) {
+    if let Some(arbiter) = arbiter {
+        if arbiter == sender || arbiter == recipient {
+            panic!("arbiter must be a third party");
+        }
+    }
+}
+
+/// Split an escrow swap between its parties outside the hashlock: `recipient_amount`
+/// goes to the recipient or its payout split, the rest back to the sender. No
+/// integrator fee is taken. `arbiter` is the deciding arbiter, if any. As with
+/// `cancel`, a denied sender can't get funds back this way.
+pub fn settle(env: &Env, swap_id: &Bytes, swap: HTLCSwap, recipient_amount: i128, arbiter: Option<Address>) {
+    if recipient_amount < 0 || recipient_amount > swap.amount {
+        panic!("invalid settlement amount");
+    }
+    let sender_amount = swap.amount - recipient_amount;
+
+    if recipient_amount > 0 {
+        denylist::ensure_payees_allowed(env, &swap.recipient, &swap.payouts, &None, &None);
+        payout::pay(env, &swap.token, recipient_amount, &swap.recipient, &swap.payouts);
+    }
+    if sender_amount > 0 {
+        denylist::ensure_allowed(env, &swap.sender);
+        let token_client = token::Client::new(env, &swap.token);
+        token_client.transfer(&env.current_contract_address(), &swap.sender, &sender_amount);
+    }
+
+    let mut updated_swap = swap;
+    updated_swap.is_settled = true;
+    close_swap(env, swap_id, &updated_swap);
+
+    events::swap_settled(
+        env,
+        swap_id,
+        &updated_swap.hashlock,
+        SwapSettled {
+            seq: events::next_seq(env),
+            arbiter,
+            token: updated_swap.token,
+            recipient_amount,
+            sender_amount,
+        },
+    );
+}
@@ -0,0 +1,506 @@
+use soroban_sdk::{contracttype, symbol_short, Address, Bytes, BytesN, Env, Symbol, Vec};
+
+use crate::{DataKey, DutchAuction, Operation, Role, SwapOptions};
+
+/// Version of the event schema, published as the second topic of every event.
+/// Bump it whenever a payload struct changes shape.
+///
+/// Every payload carries `seq`, a contract-wide counter incremented once per
+/// event, so consumers can detect gaps (see `get_event_seq`).
+pub const EVENT_VERSION: u32 = 3;
+
+// Event symbols
+pub const SWAP_CREATED: Symbol = symbol_short!("CREATED");
+pub const SWAP_WITHDRAWN: Symbol = symbol_short!("WITHDRAWN");
+pub const SWAP_REFUNDED: Symbol = symbol_short!("REFUNDED");
+pub const SWAP_CANCELLED: Symbol = symbol_short!("CANCELLED");
+pub const SWAP_SETTLED: Symbol = symbol_short!("SETTLED");
+pub const SWAP_APPROVED: Symbol = symbol_short!("APPROVED");
+pub const TIMELOCK_EXTENDED: Symbol = symbol_short!("EXTENDED");
+pub const SWAP_TOPPED_UP: Symbol = symbol_short!("TOPPED_UP");
+pub const INTEGRATOR_FEE_PAID: Symbol = symbol_short!("FEE_PAID");
+pub const WITHDRAW_SWAPPED: Symbol = symbol_short!("SWAPPED");
+pub const SWAP_GROUP_CREATED: Symbol = symbol_short!("GROUPED");
+pub const PREMIUM_PAID: Symbol = symbol_short!("PREMIUM");
+pub const PREIMAGE_REVEALED: Symbol = symbol_short!("REVEALED");
+pub const RESOLVER_BOND: Symbol = symbol_short!("BOND");
+pub const RESOLVER_SLASHED: Symbol = symbol_short!("SLASHED");
+pub const ORDER_POSTED: Symbol = symbol_short!("ORDERED");
+pub const ORDER_FILLED: Symbol = symbol_short!("FILLED");
+pub const ORDER_CANCELLED: Symbol = symbol_short!("ORD_CNCL");
+pub const FUNDING_ATTESTED: Symbol = symbol_short!("ATTESTED");
+pub const ROLE_GRANTED: Symbol = symbol_short!("GRANTED");
+pub const ROLE_REVOKED: Symbol = symbol_short!("REVOKED");
+pub const OWNERSHIP_PROPOSED: Symbol = symbol_short!("PROPOSED");
+pub const OWNERSHIP_TRANSFERRED: Symbol = symbol_short!("OWNER");
+pub const PAUSED: Symbol = symbol_short!("PAUSED");
+pub const UNPAUSED: Symbol = symbol_short!("UNPAUSED");
+pub const OPERATION_SCHEDULED: Symbol = symbol_short!("SCHEDULED");
+pub const OPERATION_EXECUTED: Symbol = symbol_short!("EXECUTED");
+pub const OPERATION_CANCELLED: Symbol = symbol_short!("OP_CANCEL");
+pub const ADDRESS_DENIED: Symbol = symbol_short!("DENIED");
+pub const ADDRESS_ALLOWED: Symbol = symbol_short!("ALLOWED");
+pub const SKIMMED: Symbol = symbol_short!("SKIMMED");
+
+/// Payload of a `CREATED` event; topics are `(CREATED, version, swap_id, hashlock)`.
+/// `options` equals `SwapOptions::none` for a plain HTLC; anything else may
+/// redirect, split or return the funds differently.
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct SwapCreated {
+    pub seq: u64,
+    pub sender: Address,
+    pub recipient: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub timelock: u64,
+    pub options: SwapOptions,
+}
+
+/// Payload of a `WITHDRAWN` event; topics are `(WITHDRAWN, version, swap_id, hashlock)`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct SwapWithdrawn {
+    pub seq: u64,
+    pub sender: Address,
+    pub recipient: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub preimage: Bytes,
+}
+
+/// Payload of a `REFUNDED` event; topics are `(REFUNDED, version, swap_id, hashlock)`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct SwapRefunded {
+    pub seq: u64,
+    pub sender: Address,
+    pub recipient: Address,
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// Payload of a `CANCELLED` event; topics are `(CANCELLED, version, swap_id, hashlock)`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct SwapCancelled {
+    pub seq: u64,
+    pub sender: Address,
+    pub recipient: Address,
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// Payload of a `SETTLED` event; topics are `(SETTLED, version, swap_id, hashlock)`.
+/// `arbiter` is set when the arbiter decided and empty for a joint settlement.
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct SwapSettled {
+    pub seq: u64,
+    pub arbiter: Option<Address>,
+    pub token: Address,
+    pub recipient_amount: i128,
+    pub sender_amount: i128,
+}
+
+/// Payload of an `APPROVED` event; topics are `(APPROVED, version, swap_id, hashlock)`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct SwapApproved {
+    pub seq: u64,
+    pub approver: Address,
+    pub approvals: u32, // Approvals recorded so far, including this one
+}
+
+/// Payload of an `EXTENDED` event; topics are `(EXTENDED, version, swap_id, hashlock)`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct TimelockExtended {
+    pub seq: u64,
+    pub old_timelock: u64,
+    pub new_timelock: u64,
+}
+
+/// Payload of a `TOPPED_UP` event; topics are `(TOPPED_UP, version, swap_id, hashlock)`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct SwapToppedUp {
+    pub seq: u64,
+    pub extra_amount: i128,
+    pub amount: i128, // Swap amount after the top-up
+}
+
+/// Payload of a `FEE_PAID` event; topics are `(FEE_PAID, version, swap_id, hashlock)`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct IntegratorFeePaid {
+    pub seq: u64,
+    pub integrator: Address,
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// Payload of a `SWAPPED` event; topics are `(SWAPPED, version, swap_id, hashlock)`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct WithdrawSwapped {
+    pub seq: u64,
+    pub router: Address,
+    pub token_out: Address,
+    pub amount_in: i128,
+    pub amount_out: i128,
+}
+
+/// Payload of a `GROUPED` event; topics are `(GROUPED, version, group_id, hashlock)`.
+/// Each leg also emits its own `CREATED` event.
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct SwapGroupCreated {
+    pub seq: u64,
+    pub sender: Address,
+    pub timelock: u64,
+    pub swap_ids: Vec<Bytes>,
+}
+
+/// Payload of a `PREMIUM` event; topics are `(PREMIUM, version, swap_id, hashlock)`.
+/// `to` is the recipient after a refund and the sender otherwise.
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct PremiumPaid {
+    pub seq: u64,
+    pub to: Address,
+    pub amount: i128,
+}
+
+/// Payload of a `REVEALED` event; topics are `(REVEALED, version, hashlock)`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct PreimageRevealed {
+    pub seq: u64,
+    pub preimage: Bytes,
+}
+
+/// Payload of a `BOND` event, published whenever a resolver's bond changes;
+/// topics are `(BOND, version, resolver)`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct ResolverBondUpdated {
+    pub seq: u64,
+    pub bond: i128,
+    pub unbonding: i128,
+    pub unbond_at: u64,
+}
+
+/// Payload of a `SLASHED` event; topics are `(SLASHED, version, swap_id, hashlock)`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct ResolverSlashed {
+    pub seq: u64,
+    pub resolver: Address,
+    pub to: Address,
+    pub amount: i128,
+}
+
+/// Payload of an `ORDERED` event; topics are `(ORDERED, version, order_id, hashlock)`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct OrderPosted {
+    pub seq: u64,
+    pub maker: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub auction: DutchAuction,
+    pub swap_duration: u64,
+}
+
+/// Payload of a `FILLED` event; topics are `(FILLED, version, order_id, hashlock)`.
+/// The created swap also emits its own `CREATED` event.
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct OrderFilled {
+    pub seq: u64,
+    pub resolver: Address,
+    pub swap_id: Bytes,
+    pub rate: i128,
+    pub taking_amount: i128, // Owed by the resolver on the destination chain
+}
+
+/// Payload of an `ORD_CNCL` event; topics are `(ORD_CNCL, version, order_id, hashlock)`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct OrderCancelled {
+    pub seq: u64,
+    pub maker: Address,
+    pub amount: i128,
+}
+
+/// Payload of an `ATTESTED` event, published after the `CREATED` event of a swap
+/// backed by an attested EVM funding; topics are `(ATTESTED, version, swap_id, hashlock)`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct FundingAttested {
+    pub seq: u64,
+    pub chain_id: u64,
+    pub contract: BytesN<20>,
+    pub evm_swap_id: BytesN<32>,
+}
+
+/// Payload of `GRANTED` and `REVOKED` events; topics are `(GRANTED, version, account)`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct RoleChanged {
+    pub seq: u64,
+    pub role: Role,
+}
+
+/// Payload of a `PROPOSED` event; topics are `(PROPOSED, version, new_owner)`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct OwnershipProposed {
+    pub seq: u64,
+    pub owner: Address, // Current owner
+}
+
+/// Payload of an `OWNER` event; topics are `(OWNER, version, new_owner)`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct OwnershipTransferred {
+    pub seq: u64,
+    pub previous_owner: Address,
+}
+
+/// Payload of `PAUSED` and `UNPAUSED` events; topics are `(PAUSED, version)`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct PauseChanged {
+    pub seq: u64,
+    pub by: Address,
+}
+
+/// Payload of a `SCHEDULED` event; topics are `(SCHEDULED, version, operation_id)`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct OperationScheduled {
+    pub seq: u64,
+    pub operation: Operation,
+    pub proposer: Address,
+    pub ready_at: u64,
+}
+
+/// Payload of an `EXECUTED` event; topics are `(EXECUTED, version, operation_id)`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct OperationExecuted {
+    pub seq: u64,
+    pub operation: Operation,
+}
+
+/// Payload of an `OP_CANCEL` event; topics are `(OP_CANCEL, version, operation_id)`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct OperationCancelled {
+    pub seq: u64,
+    pub by: Address,
+}
+
+/// Payload of `DENIED` and `ALLOWED` events; topics are `(DENIED, version, account)`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct DenylistChanged {
+    pub seq: u64,
+}
+
+/// Payload of a `SKIMMED` event; topics are `(SKIMMED, version, token)`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct TokensSkimmed {
+    pub seq: u64,
+    pub to: Address,
+    pub amount: i128,
+}
+
+/// Allocate the sequence number for the next event; the first event gets 1
+pub(crate) fn next_seq(env: &Env) -> u64 {
+    let seq = last_seq(env) + 1;
+    env.storage().instance().set(&DataKey::EventSeq, &seq);
+    seq
+}
+
+pub(crate) fn last_seq(env: &Env) -> u64 {
+    env.storage().instance().get(&DataKey::EventSeq).unwrap_or(0)
+}
+
+pub(crate) fn swap_created(env: &Env, swap_id: &Bytes, hashlock: &Bytes, event: SwapCreated) {
+    env.events().publish(
+        (SWAP_CREATED, EVENT_VERSION, swap_id.clone(), hashlock.clone()),
+        event,
+    );
+}
+
+pub(crate) fn swap_withdrawn(env: &Env, swap_id: &Bytes, hashlock: &Bytes, event: SwapWithdrawn) {
+    env.events().publish(
+        (SWAP_WITHDRAWN, EVENT_VERSION, swap_id.clone(), hashlock.clone()),
+        event,
+    );
+}
+
+pub(crate) fn swap_refunded(env: &Env, swap_id: &Bytes, hashlock: &Bytes, event: SwapRefunded) {
+    env.events().publish(
+        (SWAP_REFUNDED, EVENT_VERSION, swap_id.clone(), hashlock.clone()),
+        event,
+    );
+}
+
+pub(crate) fn swap_cancelled(env: &Env, swap_id: &Bytes, hashlock: &Bytes, event: SwapCancelled) {
+    env.events().publish(
+        (SWAP_CANCELLED, EVENT_VERSION, swap_id.clone(), hashlock.clone()),
+        event,
+    );
+}
+
+pub(crate) fn swap_settled(env: &Env, swap_id: &Bytes, hashlock: &Bytes, event: SwapSettled) {
+    env.events().publish(
+        (SWAP_SETTLED, EVENT_VERSION, swap_id.clone(), hashlock.clone()),
+        event,
+    );
+}
+
+pub(crate) fn swap_approved(env: &Env, swap_id: &Bytes, hashlock: &Bytes, event: SwapApproved) {
+    env.events().publish(
+        (SWAP_APPROVED, EVENT_VERSION, swap_id.clone(), hashlock.clone()),
+        event,
+    );
+}
+
+pub(crate) fn timelock_extended(env: &Env, swap_id: &Bytes, hashlock: &Bytes, event: TimelockExtended) {
+    env.events().publish(
+        (TIMELOCK_EXTENDED, EVENT_VERSION, swap_id.clone(), hashlock.clone()),
+        event,
+    );
+}
+
+pub(crate) fn swap_topped_up(env: &Env, swap_id: &Bytes, hashlock: &Bytes, event: SwapToppedUp) {
+    env.events().publish(
+        (SWAP_TOPPED_UP, EVENT_VERSION, swap_id.clone(), hashlock.clone()),
+        event,
+    );
+}
+
+pub(crate) fn integrator_fee_paid(env: &Env, swap_id: &Bytes, hashlock: &Bytes, event: IntegratorFeePaid) {
+    env.events().publish(
+        (INTEGRATOR_FEE_PAID, EVENT_VERSION, swap_id.clone(), hashlock.clone()),
+        event,
+    );
+}
+
+pub(crate) fn withdraw_swapped(env: &Env, swap_id: &Bytes, hashlock: &Bytes, event: WithdrawSwapped) {
+    env.events().publish(
+        (WITHDRAW_SWAPPED, EVENT_VERSION, swap_id.clone(), hashlock.clone()),
+        event,
+    );
+}
+
+pub(crate) fn swap_group_created(env: &Env, group_id: &Bytes, hashlock: &Bytes, event: SwapGroupCreated) {
+    env.events().publish(
+        (SWAP_GROUP_CREATED, EVENT_VERSION, group_id.clone(), hashlock.clone()),
+        event,
+    );
+}
+
+pub(crate) fn premium_paid(env: &Env, swap_id: &Bytes, hashlock: &Bytes, event: PremiumPaid) {
+    env.events().publish(
+        (PREMIUM_PAID, EVENT_VERSION, swap_id.clone(), hashlock.clone()),
+        event,
+    );
+}
+
+pub(crate) fn preimage_revealed(env: &Env, hashlock: &Bytes, event: PreimageRevealed) {
+    env.events().publish((PREIMAGE_REVEALED, EVENT_VERSION, hashlock.clone()), event);
+}
+
+pub(crate) fn resolver_bond_updated(env: &Env, resolver: &Address, event: ResolverBondUpdated) {
+    env.events().publish((RESOLVER_BOND, EVENT_VERSION, resolver.clone()), event);
+}
+
+pub(crate) fn resolver_slashed(env: &Env, swap_id: &Bytes, hashlock: &Bytes, event: ResolverSlashed) {
+    env.events().publish(
+        (RESOLVER_SLASHED, EVENT_VERSION, swap_id.clone(), hashlock.clone()),
+        event,
+    );
+}
+
+pub(crate) fn order_posted(env: &Env, order_id: &Bytes, hashlock: &Bytes, event: OrderPosted) {
+    env.events().publish(
+        (ORDER_POSTED, EVENT_VERSION, order_id.clone(), hashlock.clone()),
+        event,
+    );
+}
+
+pub(crate) fn order_filled(env: &Env, order_id: &Bytes, hashlock: &Bytes, event: OrderFilled) {
+    env.events().publish(
+        (ORDER_FILLED, EVENT_VERSION, order_id.clone(), hashlock.clone()),
+        event,
+    );
+}
+
+pub(crate) fn order_cancelled(env: &Env, order_id: &Bytes, hashlock: &Bytes, event: OrderCancelled) {
+    env.events().publish(
+        (ORDER_CANCELLED, EVENT_VERSION, order_id.clone(), hashlock.clone()),
+        event,
+    );
+}
+
+pub(crate) fn funding_attested(env: &Env, swap_id: &Bytes, hashlock: &Bytes, event: FundingAttested) {
+    env.events().publish(
+        (FUNDING_ATTESTED, EVENT_VERSION, swap_id.clone(), hashlock.clone()),
+        event,
+    );
+}
+
+pub(crate) fn role_granted(env: &Env, account: &Address, event: RoleChanged) {
+    env.events().publish((ROLE_GRANTED, EVENT_VERSION, account.clone()), event);
+}
+
+pub(crate) fn role_revoked(env: &Env, account: &Address, event: RoleChanged) {
+    env.events().publish((ROLE_REVOKED, EVENT_VERSION, account.clone()), event);
+}
+
+pub(crate) fn ownership_proposed(env: &Env, new_owner: &Address, event: OwnershipProposed) {
+    env.events().publish((OWNERSHIP_PROPOSED, EVENT_VERSION, new_owner.clone()), event);
+}
+
+pub(crate) fn ownership_transferred(env: &Env, new_owner: &Address, event: OwnershipTransferred) {
+    env.events().publish((OWNERSHIP_TRANSFERRED, EVENT_VERSION, new_owner.clone()), event);
+}
+
+pub(crate) fn paused(env: &Env, event: PauseChanged) {
+    env.events().publish((PAUSED, EVENT_VERSION), event);
+}
+
+pub(crate) fn unpaused(env: &Env, event: PauseChanged) {
+    env.events().publish((UNPAUSED, EVENT_VERSION), event);
+}
+
+pub(crate) fn operation_scheduled(env: &Env, id: u32, event: OperationScheduled) {
+    env.events().publish((OPERATION_SCHEDULED, EVENT_VERSION, id), event);
+}
+
+pub(crate) fn operation_executed(env: &Env, id: u32, event: OperationExecuted) {
+    env.events().publish((OPERATION_EXECUTED, EVENT_VERSION, id), event);
+}
+
+pub(crate) fn operation_cancelled(env: &Env, id: u32, event: OperationCancelled) {
+    env.events().publish((OPERATION_CANCELLED, EVENT_VERSION, id), event);
+}
+
+pub(crate) fn address_denied(env: &Env, account: &Address, event: DenylistChanged) {
+    env.events().publish((ADDRESS_DENIED, EVENT_VERSION, account.clone()), event);
+}
+
+pub(crate) fn address_allowed(env: &Env, account: &Address, event: DenylistChanged) {
+    env.events().publish((ADDRESS_ALLOWED, EVENT_VERSION, account.clone()), event);
+}
+
+pub(crate) fn tokens_skimmed(env: &Env, token: &Address, event: TokensSkimmed) {
+    env.events().publish((SKIMMED, EVENT_VERSION, token.clone()), event);
+}
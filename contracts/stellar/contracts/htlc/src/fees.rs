@@ -0,0 +1,73 @@
+use soroban_sdk::{token, Address, Bytes, Env};
+
+use crate::events::{self, IntegratorFeePaid};
+use crate::payout::TOTAL_BPS;
+use crate::{bump_persistent, DataKey, HTLCSwap};
+
+pub fn max_integrator_fee_bps(env: &Env) -> u32 {
+    env.storage().instance().get(&DataKey::MaxIntegratorFeeBps).unwrap_or(0)
+}
+
+pub fn set_max_integrator_fee_bps(env: &Env, bps: u32) {
+    if bps > TOTAL_BPS {
+        panic!("fee cannot exceed 10000 bps");
+    }
+    env.storage().instance().set(&DataKey::MaxIntegratorFeeBps, &bps);
+}
+
+pub fn validate(env: &Env, integrator: &Option<Address>, bps: u32) {
+    if integrator.is_none() {
+        if bps != 0 {
+            panic!("integrator fee set without integrator");
+        }
+        return;
+    }
+    if bps == 0 {
+        panic!("integrator fee must be positive");
+    }
+    if bps > max_integrator_fee_bps(env) {
+        panic!("integrator fee exceeds cap");
+    }
+}
+
+pub fn accrued(env: &Env, integrator: &Address, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::IntegratorAccrued(integrator.clone(), token.clone()))
+        .unwrap_or(0)
+}
+
+/// Pay the integrator its share of a withdrawn swap and return the fee
+pub fn pay_integrator(env: &Env, swap_id: &Bytes, swap: &HTLCSwap) -> i128 {
+    let Some(integrator) = &swap.integrator else {
+        return 0;
+    };
+
+    let amount = swap.amount * swap.integrator_fee_bps as i128 / TOTAL_BPS as i128;
+    if amount == 0 {
+        return 0;
+    }
+
+    let token_client = token::Client::new(env, &swap.token);
+    token_client.transfer(&env.current_contract_address(), integrator, &amount);
+
+    let key = DataKey::IntegratorAccrued(integrator.clone(), swap.token.clone());
+    env.storage()
+        .persistent()
+        .set(&key, &(accrued(env, integrator, &swap.token) + amount));
+    bump_persistent(env, &key);
+
+    events::integrator_fee_paid(
+        env,
+        swap_id,
+        &swap.hashlock,
+        IntegratorFeePaid {
+            seq: events::next_seq(env),
+            integrator: integrator.clone(),
+            token: swap.token.clone(),
+            amount,
+        },
+    );
+
+    amount
+}
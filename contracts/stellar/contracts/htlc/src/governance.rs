@@ -0,0 +1,193 @@
+use soroban_sdk::{contracttype, Address, BytesN, Env, Vec};
+
+use crate::attestation::{self, Committee, FundingAsset};
+use crate::events::{self, OperationCancelled, OperationExecuted, OperationScheduled};
+use crate::payout::TOTAL_BPS;
+use crate::roles::{self, Role};
+use crate::{bump_instance, DataKey};
+
+/// Default delay between scheduling and executing an operation (2 days)
+pub const DEFAULT_MIN_DELAY: u64 = 2 * 24 * 60 * 60;
+
+/// Upper bound for the governance delay, the timelock horizon and the unbond delay (30 days)
+pub const MAX_DELAY: u64 = 30 * 24 * 60 * 60;
+
+/// Maximum number of operations waiting in the queue
+pub const MAX_PENDING_OPERATIONS: u32 = 20;
+
+/// Configuration change that only takes effect after the governance delay
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub enum Operation {
+    SetMaxIntegratorFeeBps(u32), // FeeManager
+    DelistToken(Address),        // TokenManager; blocks new swaps in the token
+    RelistToken(Address),        // TokenManager
+    Upgrade(BytesN<32>),         // Upgrader; hash of the uploaded Wasm
+    SetMinDelay(u64),            // Owner
+    SetMaxTimelockHorizon(u64),  // Owner; seconds `extend_timelock` may add past a swap's initial timelock
+    SetRouter(Address),          // Owner; router `withdraw_and_swap` may use
+    SetMinBond(i128),            // Owner
+    SetUnbondDelay(u64),         // Owner; also how long bonds stay slashable after their swaps expire
+    SetAttestationCommittee(Committee), // Owner
+    RemoveAttestationCommittee,  // Owner; lets `create_swap` open unattested swaps again
+    SetFundingAsset(u64, BytesN<20>, FundingAsset), // Owner; (chain_id, EVM HTLC contract, asset)
+}
+
+/// Queued operation, executable by anyone from `ready_at`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct ScheduledOperation {
+    pub operation: Operation,
+    pub proposer: Address,
+    pub ready_at: u64,
+}
+
+fn role_for(operation: &Operation) -> Role {
+    match operation {
+        Operation::SetMaxIntegratorFeeBps(_) => Role::FeeManager,
+        Operation::DelistToken(_) | Operation::RelistToken(_) => Role::TokenManager,
+        Operation::Upgrade(_) => Role::Upgrader,
+        Operation::SetMinDelay(_)
+        | Operation::SetMaxTimelockHorizon(_)
+        | Operation::SetRouter(_)
+        | Operation::SetMinBond(_)
+        | Operation::SetUnbondDelay(_)
+        | Operation::SetAttestationCommittee(_)
+        | Operation::RemoveAttestationCommittee
+        | Operation::SetFundingAsset(..) => Role::Owner,
+    }
+}
+
+/// Reject operations that would fail when executed
+fn validate(operation: &Operation) {
+    match operation {
+        Operation::SetMaxIntegratorFeeBps(bps) if *bps > TOTAL_BPS => panic!("fee cannot exceed 10000 bps"),
+        Operation::SetMinDelay(0) => panic!("delay must be positive"),
+        Operation::SetMinDelay(delay) if *delay > MAX_DELAY => panic!("delay exceeds maximum"),
+        Operation::SetMaxTimelockHorizon(0) => panic!("horizon must be positive"),
+        Operation::SetMaxTimelockHorizon(horizon) if *horizon > MAX_DELAY => panic!("horizon exceeds maximum"),
+        Operation::SetUnbondDelay(delay) if *delay > MAX_DELAY => panic!("unbond delay exceeds maximum"),
+        Operation::SetMinBond(amount) if *amount < 0 => panic!("minimum bond cannot be negative"),
+        Operation::SetAttestationCommittee(committee) => attestation::validate(committee),
+        _ => {}
+    }
+}
+
+pub fn min_delay(env: &Env) -> u64 {
+    env.storage().instance().get(&DataKey::MinDelay).unwrap_or(DEFAULT_MIN_DELAY)
+}
+
+pub fn is_delisted(env: &Env, token: &Address) -> bool {
+    env.storage().instance().has(&DataKey::Delisted(token.clone()))
+}
+
+pub fn pending_ids(env: &Env) -> Vec<u32> {
+    env.storage()
+        .instance()
+        .get(&DataKey::PendingOperations)
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn get(env: &Env, id: u32) -> Option<ScheduledOperation> {
+    env.storage().instance().get(&DataKey::Operation(id))
+}
+
+fn remove(env: &Env, id: u32) -> ScheduledOperation {
+    let scheduled = get(env, id).expect("operation not found");
+    env.storage().instance().remove(&DataKey::Operation(id));
+
+    let mut ids = pending_ids(env);
+    let pos = ids.first_index_of(id).expect("operation not pending");
+    ids.remove(pos);
+    env.storage().instance().set(&DataKey::PendingOperations, &ids);
+    bump_instance(env);
+    scheduled
+}
+
+/// Queue an operation proposed by a holder of its role and return its id
+pub fn schedule(env: &Env, caller: &Address, operation: Operation) -> u32 {
+    roles::require_role(env, caller, role_for(&operation));
+    validate(&operation);
+
+    let mut ids = pending_ids(env);
+    if ids.len() >= MAX_PENDING_OPERATIONS {
+        panic!("too many pending operations");
+    }
+
+    let id: u32 = env.storage().instance().get(&DataKey::NextOperationId).unwrap_or(0);
+    env.storage().instance().set(&DataKey::NextOperationId, &(id + 1));
+    ids.push_back(id);
+    env.storage().instance().set(&DataKey::PendingOperations, &ids);
+
+    let scheduled = ScheduledOperation {
+        operation,
+        proposer: caller.clone(),
+        ready_at: env.ledger().timestamp() + min_delay(env),
+    };
+    env.storage().instance().set(&DataKey::Operation(id), &scheduled);
+    bump_instance(env);
+
+    events::operation_scheduled(
+        env,
+        id,
+        OperationScheduled {
+            seq: events::next_seq(env),
+            operation: scheduled.operation,
+            proposer: scheduled.proposer,
+            ready_at: scheduled.ready_at,
+        },
+    );
+    id
+}
+
+/// Drop a queued operation; the owner or a holder of the operation's role may cancel
+pub fn cancel(env: &Env, caller: &Address, id: u32) {
+    caller.require_auth();
+    let scheduled = get(env, id).expect("operation not found");
+    if !roles::has_role(env, Role::Owner, caller) && !roles::has_role(env, role_for(&scheduled.operation), caller) {
+        panic!("caller lacks role");
+    }
+    remove(env, id);
+
+    events::operation_cancelled(
+        env,
+        id,
+        OperationCancelled {
+            seq: events::next_seq(env),
+            by: caller.clone(),
+        },
+    );
+}
+
+/// Remove a queued operation whose delay has passed and return it for the caller
+/// to apply
+pub fn take_ready(env: &Env, id: u32) -> Operation {
+    let scheduled = get(env, id).expect("operation not found");
+    if env.ledger().timestamp() < scheduled.ready_at {
+        panic!("operation not ready");
+    }
+    remove(env, id);
+
+    events::operation_executed(
+        env,
+        id,
+        OperationExecuted {
+            seq: events::next_seq(env),
+            operation: scheduled.operation.clone(),
+        },
+    );
+    scheduled.operation
+}
+
+pub fn set_delisted(env: &Env, token: &Address, delisted: bool) {
+    let key = DataKey::Delisted(token.clone());
+    if delisted {
+        env.storage().instance().set(&key, &true);
+    } else {
+        env.storage().instance().remove(&key);
+    }
+}
+
+pub fn set_min_delay(env: &Env, delay: u64) {
+    env.storage().instance().set(&DataKey::MinDelay, &delay);
+}
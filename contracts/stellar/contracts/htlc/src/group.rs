@@ -0,0 +1,49 @@
+use soroban_sdk::{contracttype, Address, Bytes, Env, Vec};
+
+use crate::{bump_persistent, DataKey};
+
+/// Maximum number of legs in a swap group
+pub const MAX_GROUP_LEGS: u32 = 10;
+
+/// One swap of a group, created with the group's sender, hashlock and timelock
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct GroupLeg {
+    pub swap_id: Bytes,
+    pub recipient: Address,
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// Swaps sharing one hashlock, released together by `withdraw_group`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct SwapGroup {
+    pub sender: Address,
+    pub hashlock: Bytes,
+    pub timelock: u64,
+    pub swap_ids: Vec<Bytes>,
+}
+
+pub fn validate_legs(legs: &Vec<GroupLeg>) {
+    if legs.is_empty() {
+        panic!("group must have at least one leg");
+    }
+    if legs.len() > MAX_GROUP_LEGS {
+        panic!("too many group legs");
+    }
+}
+
+pub fn get(env: &Env, group_id: &Bytes) -> Option<SwapGroup> {
+    env.storage().persistent().get(&DataKey::Group(group_id.clone()))
+}
+
+pub fn load(env: &Env, group_id: &Bytes) -> SwapGroup {
+    get(env, group_id).expect("group not found")
+}
+
+pub fn store(env: &Env, group_id: &Bytes, group: &SwapGroup) {
+    let key = DataKey::Group(group_id.clone());
+    env.storage().persistent().set(&key, group);
+    bump_persistent(env, &key);
+}
@@ -0,0 +1,110 @@
+use soroban_sdk::{Address, Bytes, Env, Vec};
+
+use crate::{bump_persistent as bump, DataKey};
+
+/// Maximum number of entries returned by a single paginated or bulk read. A page
+/// reads one persistent entry per item (an index entry or a swap) plus the counter,
+/// the instance and the code entry, which keeps it under the network's
+/// per-transaction read-entry limit.
+pub const MAX_PAGE_SIZE: u32 = 30;
+
+fn set(env: &Env, key: &DataKey, swap_id: &Bytes) {
+    env.storage().persistent().set(key, swap_id);
+    bump(env, key);
+}
+
+fn read_page<F>(env: &Env, count_key: DataKey, item_key: F, cursor: u32, limit: u32) -> Vec<Bytes>
+where
+    F: Fn(u32) -> DataKey,
+{
+    let count: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
+    let end = cursor.saturating_add(limit.min(MAX_PAGE_SIZE)).min(count);
+
+    let mut page = Vec::new(env);
+    for i in cursor..end {
+        let swap_id: Bytes = env
+            .storage()
+            .persistent()
+            .get(&item_key(i))
+            .expect("index entry missing");
+        page.push_back(swap_id);
+    }
+    page
+}
+
+fn next_position(env: &Env, count_key: &DataKey) -> u32 {
+    let count: u32 = env.storage().persistent().get(count_key).unwrap_or(0);
+    env.storage().persistent().set(count_key, &(count + 1));
+    bump(env, count_key);
+    count
+}
+
+/// Record a newly created swap in the sender, recipient and hashlock indexes
+pub fn add_swap(env: &Env, swap_id: &Bytes, sender: &Address, recipient: &Address, hashlock: &Bytes) {
+    let pos = next_position(env, &DataKey::SenderSwapCount(sender.clone()));
+    set(env, &DataKey::SenderSwap(sender.clone(), pos), swap_id);
+
+    let pos = next_position(env, &DataKey::RecipientSwapCount(recipient.clone()));
+    set(env, &DataKey::RecipientSwap(recipient.clone(), pos), swap_id);
+
+    set(env, &DataKey::HashlockSwap(hashlock.clone()), swap_id);
+}
+
+pub fn swaps_by_sender(env: &Env, sender: &Address, cursor: u32, limit: u32) -> Vec<Bytes> {
+    read_page(
+        env,
+        DataKey::SenderSwapCount(sender.clone()),
+        |i| DataKey::SenderSwap(sender.clone(), i),
+        cursor,
+        limit,
+    )
+}
+
+pub fn swaps_by_recipient(env: &Env, recipient: &Address, cursor: u32, limit: u32) -> Vec<Bytes> {
+    read_page(
+        env,
+        DataKey::RecipientSwapCount(recipient.clone()),
+        |i| DataKey::RecipientSwap(recipient.clone(), i),
+        cursor,
+        limit,
+    )
+}
+
+pub fn swap_id_by_hashlock(env: &Env, hashlock: &Bytes) -> Option<Bytes> {
+    env.storage().persistent().get(&DataKey::HashlockSwap(hashlock.clone()))
+}
+
+/// Add a swap to the set of unresolved swaps
+pub fn add_open_swap(env: &Env, swap_id: &Bytes) {
+    let pos = next_position(env, &DataKey::OpenSwapCount);
+    set(env, &DataKey::OpenSwap(pos), swap_id);
+
+    let pos_key = DataKey::OpenSwapPos(swap_id.clone());
+    env.storage().persistent().set(&pos_key, &pos);
+    bump(env, &pos_key);
+}
+
+/// Remove a resolved swap from the open set by moving the last entry into its slot
+pub fn remove_open_swap(env: &Env, swap_id: &Bytes) {
+    let storage = env.storage().persistent();
+    let pos_key = DataKey::OpenSwapPos(swap_id.clone());
+    let pos: u32 = storage.get(&pos_key).expect("swap not in open set");
+    let last: u32 = storage.get::<_, u32>(&DataKey::OpenSwapCount).expect("open set empty") - 1;
+
+    if pos != last {
+        let moved: Bytes = storage.get(&DataKey::OpenSwap(last)).expect("index entry missing");
+        set(env, &DataKey::OpenSwap(pos), &moved);
+        let moved_pos_key = DataKey::OpenSwapPos(moved);
+        storage.set(&moved_pos_key, &pos);
+        bump(env, &moved_pos_key);
+    }
+
+    storage.remove(&DataKey::OpenSwap(last));
+    storage.remove(&pos_key);
+    storage.set(&DataKey::OpenSwapCount, &last);
+    bump(env, &DataKey::OpenSwapCount);
+}
+
+pub fn open_swaps(env: &Env, cursor: u32, limit: u32) -> Vec<Bytes> {
+    read_page(env, DataKey::OpenSwapCount, DataKey::OpenSwap, cursor, limit)
+}
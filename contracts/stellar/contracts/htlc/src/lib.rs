@@ -1,18 +1,99 @@
 #![no_std]
+#![allow(clippy::too_many_arguments)]
 
 use soroban_sdk::{
-    contract, contractimpl, contracttype, token, Address, Env, Bytes, symbol_short, Symbol
+    contract, contractimpl, contracttype, token, Address, Env, Bytes, BytesN, Vec
 };
 
-// Event symbols
-const SWAP_CREATED: Symbol = symbol_short!("CREATED");
-const SWAP_WITHDRAWN: Symbol = symbol_short!("WITHDRAWN");
-const SWAP_REFUNDED: Symbol = symbol_short!("REFUNDED");
+mod approval;
+mod attestation;
+mod callback;
+mod denylist;
+mod escrow;
+pub mod events;
+mod fees;
+mod governance;
+mod group;
+mod index;
+mod order;
+mod payout;
+mod premium;
+mod resolver;
+mod roles;
+mod router;
+mod stats;
+
+pub use events::{
+    DenylistChanged, FundingAttested, IntegratorFeePaid, OperationCancelled, OperationExecuted,
+    OperationScheduled, OrderCancelled, OrderFilled, OrderPosted, OwnershipProposed,
+    OwnershipTransferred, PauseChanged, PreimageRevealed, PremiumPaid, ResolverBondUpdated,
+    ResolverSlashed, RoleChanged, SwapApproved, SwapCancelled, SwapCreated, SwapGroupCreated,
+    SwapRefunded, SwapSettled, SwapToppedUp, SwapWithdrawn, TimelockExtended, TokensSkimmed,
+    WithdrawSwapped,
+};
+pub use approval::MAX_APPROVERS;
+pub use attestation::{Committee, EvmFunding, FundingAsset};
+pub use committee::{Attestation, Signer, MAX_SIGNERS};
+pub use callback::{WithdrawReceiver, WithdrawReceiverClient};
+pub use governance::{Operation, ScheduledOperation, DEFAULT_MIN_DELAY, MAX_DELAY, MAX_PENDING_OPERATIONS};
+pub use group::{GroupLeg, SwapGroup};
+pub use order::{DutchAuction, Order, RATE_SCALE};
+pub use payout::Payout;
+pub use resolver::{Resolver, DEFAULT_UNBOND_DELAY};
+pub use roles::Role;
+pub use router::{SwapRouter, SwapRouterClient};
+pub use stats::ParticipantStats;
+
+const DAY_IN_LEDGERS: u32 = 17280;
+const PERSISTENT_TTL_THRESHOLD: u32 = 30 * DAY_IN_LEDGERS;
+const PERSISTENT_TTL_EXTEND_TO: u32 = 120 * DAY_IN_LEDGERS;
+
+/// Default for how far past a swap's initial timelock `extend_timelock` may push it (7 days)
+pub const DEFAULT_MAX_TIMELOCK_HORIZON: u64 = 7 * 24 * 60 * 60;
 
 #[derive(Clone)]
 #[contracttype]
 pub enum DataKey {
     Swap(Bytes),
+    Admin, // Owner
+    LockedTotal(Address), // Amount of each token the contract owes, see `get_locked_total`
+    SenderSwapCount(Address),
+    SenderSwap(Address, u32),
+    RecipientSwapCount(Address),
+    RecipientSwap(Address, u32),
+    HashlockSwap(Bytes), // Most recently created swap for a hashlock
+    OpenSwapCount,
+    OpenSwap(u32),
+    OpenSwapPos(Bytes), // Position of a swap in the open set
+    EventSeq,
+    MaxTimelockHorizon,
+    MaxIntegratorFeeBps,
+    IntegratorAccrued(Address, Address), // (integrator, token) -> total fees paid
+    Router,
+    Group(Bytes),
+    Preimage(Bytes), // Revealed preimage by hashlock
+    BondToken,
+    MinBond,
+    UnbondDelay,
+    Resolver(Address),
+    Slashed(Bytes), // Amount slashed for a refunded resolver-funded swap
+    ResolverExposure(Address), // Latest timelock of the swaps a resolver funded
+    ParticipantStats(Address),
+    Order(Bytes),
+    AttestationCommittee,
+    AttestedFunding(BytesN<32>), // Id of an EVM funding backing a swap
+    SwapFunding(Bytes),          // Attested EVM funding of a swap
+    FundingAsset(u64, BytesN<20>), // (chain_id, EVM HTLC contract) -> mirrored Stellar asset
+    Role(Role, Address),
+    PendingOwner,
+    Paused,
+    MinDelay,
+    NextOperationId,
+    Operation(u32),
+    PendingOperations, // Ids of scheduled operations
+    Delisted(Address),
+    Denied(Address), // On the compliance denylist
+    Approvals(Bytes), // Approvers who consented to release a swap so far
 }
 
 #[derive(Clone)]
@@ -24,9 +105,450 @@ pub struct HTLCSwap {
     pub amount: i128,
     pub hashlock: Bytes, // SHA256 hash of the preimage
     pub timelock: u64,   // Unix timestamp when refund becomes possible
+    pub initial_timelock: u64, // Timelock at creation; bounds `extend_timelock`
     pub preimage: Option<Bytes>, // Optional preimage for unlocking
     pub is_withdrawn: bool,
     pub is_refunded: bool,
+    pub is_cancelled: bool, // Returned to sender before the timelock by `cancel`
+    pub is_settled: bool,   // Split between the parties by `arbitrate` or `settle`
+    pub payouts: Vec<Payout>, // Split of the withdrawn amount; empty pays everything to `recipient`
+    pub integrator: Option<Address>, // Originator of the swap, paid a fee on withdraw
+    pub integrator_fee_bps: u32,     // Taken from the amount before the payout split
+    pub callback: Option<Address>,   // Receives the funds and `on_htlc_withdraw` on withdraw
+    pub callback_data: Bytes,
+    pub group_id: Option<Bytes>, // Set for legs created by `create_swap_group`
+    pub premium: i128, // Locked on top of `amount`; goes to the recipient on refund, else back to the sender
+    pub resolver_sender: bool, // Funded by a bonded resolver, whose bond is slashable on refund
+    pub resolver_claims: bool, // Only bonded resolvers may withdraw on the recipient's behalf
+    pub arbiter: Option<Address>, // May release to either party before the timelock
+    pub approvers: Vec<Address>, // When set, withdrawing needs `approval_threshold` of them
+    pub approval_threshold: u32,
+}
+
+/// Optional swap features for `create_swap_with_options`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct SwapOptions {
+    pub payouts: Vec<Payout>, // At most 10 beneficiaries whose shares sum to 10000 bps
+    pub integrator: Option<Address>,
+    pub integrator_fee_bps: u32, // At most `get_max_integrator_fee_bps`; paid on withdraw only
+    pub callback: Option<Address>, // Contract implementing `WithdrawReceiver`; excludes payouts
+    pub callback_data: Bytes,      // Passed as-is to `on_htlc_withdraw`
+    pub premium: i128, // Deposited by the sender on top of `amount` to compensate the recipient on refund
+    pub resolver_sender: bool, // Sender must be a bonded resolver
+    pub resolver_claims: bool, // Replaces `withdraw_revealed` with `resolver_withdraw`
+    pub arbiter: Option<Address>, // Third party resolving disputes; enables `arbitrate` and `settle`
+    pub approvers: Vec<Address>, // At most 10 signers releasing the swap via `withdraw_approved`; excludes arbiter
+    pub approval_threshold: u32, // Approvals required, between 1 and the number of approvers
+}
+
+impl SwapOptions {
+    pub fn none(env: &Env) -> Self {
+        SwapOptions {
+            payouts: Vec::new(env),
+            integrator: None,
+            integrator_fee_bps: 0,
+            callback: None,
+            callback_data: Bytes::new(env),
+            premium: 0,
+            resolver_sender: false,
+            resolver_claims: false,
+            arbiter: None,
+            approvers: Vec::new(env),
+            approval_threshold: 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum SwapStatus {
+    Open,      // Claimable with the preimage
+    Expired,   // Timelock passed, awaiting refund
+    Withdrawn,
+    Refunded,
+    Cancelled,
+    Settled,   // Resolved by the arbiter or jointly by both parties
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct TokenReconciliation {
+    pub token: Address,
+    pub balance: i128, // Contract's actual token balance
+    pub locked: i128,  // Owed to open swaps, unfilled orders and resolver bonds, see `get_locked_total`
+    pub surplus: i128, // balance - locked; negative means the contract is insolvent
+}
+
+/// Keep a persistent entry alive; call after every write
+pub(crate) fn bump_persistent(env: &Env, key: &DataKey) {
+    env.storage()
+        .persistent()
+        .extend_ttl(key, PERSISTENT_TTL_THRESHOLD, PERSISTENT_TTL_EXTEND_TO);
+}
+
+/// Keep the contract instance, which holds the configuration, alive; called by
+/// every write of a swap, an order or a governance operation
+pub(crate) fn bump_instance(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(PERSISTENT_TTL_THRESHOLD, PERSISTENT_TTL_EXTEND_TO);
+}
+
+fn swap_status(env: &Env, swap: &HTLCSwap) -> SwapStatus {
+    if swap.is_withdrawn {
+        SwapStatus::Withdrawn
+    } else if swap.is_refunded {
+        SwapStatus::Refunded
+    } else if swap.is_cancelled {
+        SwapStatus::Cancelled
+    } else if swap.is_settled {
+        SwapStatus::Settled
+    } else if env.ledger().timestamp() >= swap.timelock {
+        SwapStatus::Expired
+    } else {
+        SwapStatus::Open
+    }
+}
+
+fn is_resolved(swap: &HTLCSwap) -> bool {
+    swap.is_withdrawn || swap.is_refunded || swap.is_cancelled || swap.is_settled
+}
+
+fn ensure_open(swap: &HTLCSwap) {
+    if swap.is_withdrawn {
+        panic!("swap already withdrawn");
+    }
+    if swap.is_refunded {
+        panic!("swap already refunded");
+    }
+    if swap.is_cancelled {
+        panic!("swap already cancelled");
+    }
+    if swap.is_settled {
+        panic!("swap already settled");
+    }
+}
+
+/// Persist a swap that just left the open state, settle its premium, record the
+/// outcome and release its liability
+fn close_swap(env: &Env, swap_id: &Bytes, swap: &HTLCSwap) {
+    store_swap(env, swap_id, swap);
+    premium::settle(env, swap_id, swap);
+    stats::record_closed(env, swap);
+    approval::clear(env, swap_id);
+    set_locked_total(
+        env,
+        &swap.token,
+        get_locked_total(env, &swap.token) - swap.amount - swap.premium,
+    );
+    index::remove_open_swap(env, swap_id);
+}
+
+fn ensure_not_paused(env: &Env) {
+    if env.storage().instance().get(&DataKey::Paused).unwrap_or(false) {
+        panic!("contract paused");
+    }
+}
+
+/// Validate, fund and store a new swap; the caller checks the sender's authorization.
+/// A `prefunded` swap uses funds the contract already holds and counts as locked.
+/// While an attestation committee is set, only `attested` swaps may be opened.
+fn open_swap(
+    env: &Env,
+    swap_id: Bytes,
+    sender: Address,
+    recipient: Address,
+    token: Address,
+    amount: i128,
+    hashlock: Bytes,
+    timelock: u64,
+    options: SwapOptions,
+    group_id: Option<Bytes>,
+    prefunded: bool,
+    attested: bool,
+) {
+    // Validate inputs
+    ensure_not_paused(env);
+    if !attested && attestation::committee(env).is_some() {
+        panic!("EVM funding attestation required");
+    }
+    if amount <= 0 {
+        panic!("amount must be positive");
+    }
+    if hashlock.len() != 32 {
+        panic!("hashlock must be 32 bytes (SHA256)");
+    }
+    if timelock <= env.ledger().timestamp() {
+        panic!("timelock must be in the future");
+    }
+    if governance::is_delisted(env, &token) {
+        panic!("token delisted");
+    }
+    denylist::ensure_allowed(env, &sender);
+    denylist::ensure_payees_allowed(env, &recipient, &options.payouts, &options.integrator, &options.callback);
+    payout::validate(&options.payouts);
+    fees::validate(env, &options.integrator, options.integrator_fee_bps);
+    callback::validate(&options.callback, &options.callback_data, !options.payouts.is_empty());
+    premium::validate(options.premium);
+    if options.resolver_sender {
+        resolver::ensure_bonded(env, &sender);
+        resolver::expose(env, &sender, timelock);
+    }
+    escrow::validate(&options.arbiter, &sender, &recipient);
+    approval::validate(&options.approvers, options.approval_threshold);
+    // `arbitrate` and `settle` would move the funds without the approvers
+    if options.arbiter.is_some() && !options.approvers.is_empty() {
+        panic!("escrow swaps cannot have approvers");
+    }
+    
+    // Check if swap_id already exists
+    if get_swap(env, &swap_id).is_some() {
+        panic!("swap_id already exists");
+    }
+    
+    // Transfer tokens and premium from sender to contract
+    if !prefunded {
+        let deposit = amount.checked_add(options.premium).expect("amount overflow");
+        let token_client = token::Client::new(env, &token);
+        token_client.transfer(&sender, &env.current_contract_address(), &deposit);
+        set_locked_total(env, &token, get_locked_total(env, &token) + deposit);
+    }
+    
+    // Create the swap
+    let created_options = options.clone();
+    let swap = HTLCSwap {
+        sender: sender.clone(),
+        recipient: recipient.clone(),
+        token: token.clone(),
+        amount,
+        hashlock: hashlock.clone(),
+        timelock,
+        initial_timelock: timelock,
+        preimage: None,
+        is_withdrawn: false,
+        is_refunded: false,
+        is_cancelled: false,
+        is_settled: false,
+        payouts: options.payouts,
+        integrator: options.integrator,
+        integrator_fee_bps: options.integrator_fee_bps,
+        callback: options.callback,
+        callback_data: options.callback_data,
+        group_id,
+        premium: options.premium,
+        resolver_sender: options.resolver_sender,
+        resolver_claims: options.resolver_claims,
+        arbiter: options.arbiter,
+        approvers: options.approvers,
+        approval_threshold: options.approval_threshold,
+    };
+    
+    // Store the swap
+    store_swap(env, &swap_id, &swap);
+    index::add_swap(env, &swap_id, &sender, &recipient, &hashlock);
+    index::add_open_swap(env, &swap_id);
+    stats::record_locked(env, &sender, &token, amount, true);
+    
+    // Emit swap created event
+    events::swap_created(
+        env,
+        &swap_id,
+        &hashlock,
+        SwapCreated {
+            seq: events::next_seq(env),
+            sender,
+            recipient,
+            token,
+            amount,
+            timelock,
+            options: created_options,
+        },
+    );
+}
+
+fn get_swap(env: &Env, swap_id: &Bytes) -> Option<HTLCSwap> {
+    env.storage().persistent().get(&DataKey::Swap(swap_id.clone()))
+}
+
+fn load_swap(env: &Env, swap_id: &Bytes) -> HTLCSwap {
+    get_swap(env, swap_id).expect("swap not found")
+}
+
+fn store_swap(env: &Env, swap_id: &Bytes, swap: &HTLCSwap) {
+    let key = DataKey::Swap(swap_id.clone());
+    env.storage().persistent().set(&key, swap);
+    bump_persistent(env, &key);
+    bump_instance(env);
+}
+
+/// Load a swap and check that `recipient` may claim it with `preimage` right now
+fn load_claimable_swap(env: &Env, swap_id: &Bytes, recipient: &Address, preimage: &Bytes) -> HTLCSwap {
+    let swap = load_swap(env, swap_id);
+    
+    // Check if already withdrawn, refunded or cancelled
+    ensure_open(&swap);
+    
+    // Verify recipient
+    if swap.recipient != *recipient {
+        panic!("unauthorized recipient");
+    }
+    
+    ensure_claimable(env, &swap, preimage);
+    swap
+}
+
+/// Check that a swap without approvers can be claimed with `preimage` right now
+fn ensure_claimable(env: &Env, swap: &HTLCSwap, preimage: &Bytes) {
+    if !swap.approvers.is_empty() {
+        panic!("swap requires approvals");
+    }
+    ensure_unlocked(env, swap, preimage);
+}
+
+/// Check that `preimage` unlocks a swap and its timelock hasn't expired
+fn ensure_unlocked(env: &Env, swap: &HTLCSwap, preimage: &Bytes) {
+    denylist::ensure_payees_allowed(env, &swap.recipient, &swap.payouts, &swap.integrator, &swap.callback);
+
+    // Verify preimage matches hashlock
+    let computed_hash = env.crypto().sha256(preimage);
+    let computed_bytes = Bytes::from_slice(env, &computed_hash.to_array());
+    if computed_bytes != swap.hashlock {
+        panic!("invalid preimage");
+    }
+    
+    // Check timelock hasn't expired
+    if env.ledger().timestamp() >= swap.timelock {
+        panic!("timelock expired");
+    }
+}
+
+/// Pay out a claimed swap: the integrator fee first, then the rest to the callback
+/// target, or to `recipient` or its payout split
+fn pay_withdrawal(env: &Env, swap_id: &Bytes, swap: &HTLCSwap, recipient: &Address) {
+    let fee = fees::pay_integrator(env, swap_id, swap);
+    match &swap.callback {
+        Some(target) => callback::pay_and_call(env, swap_id, swap, target, swap.amount - fee),
+        None => payout::pay(env, &swap.token, swap.amount - fee, recipient, &swap.payouts),
+    }
+}
+
+/// Store a preimage in the registry unless its hashlock is already known.
+/// Returns whether it was newly recorded.
+fn record_preimage(env: &Env, hashlock: &Bytes, preimage: &Bytes) -> bool {
+    let key = DataKey::Preimage(hashlock.clone());
+    if env.storage().persistent().has(&key) {
+        return false;
+    }
+    env.storage().persistent().set(&key, preimage);
+    bump_persistent(env, &key);
+    true
+}
+
+/// Mark a paid-out swap as withdrawn and emit the withdrawn event
+fn complete_withdraw(env: &Env, swap_id: &Bytes, swap: HTLCSwap, recipient: Address, preimage: Bytes) {
+    let mut updated_swap = swap;
+    updated_swap.is_withdrawn = true;
+    updated_swap.preimage = Some(preimage.clone());
+    close_swap(env, swap_id, &updated_swap);
+    record_preimage(env, &updated_swap.hashlock, &preimage);
+    
+    // Emit swap withdrawn event
+    events::swap_withdrawn(
+        env,
+        swap_id,
+        &updated_swap.hashlock,
+        SwapWithdrawn {
+            seq: events::next_seq(env),
+            sender: updated_swap.sender,
+            recipient,
+            token: updated_swap.token,
+            amount: updated_swap.amount,
+            preimage,
+        },
+    );
+}
+
+/// Return an open swap's funds to its sender before the timelock and emit the
+/// cancelled event; the caller checks who consented
+fn cancel_swap(env: &Env, swap_id: &Bytes, swap: HTLCSwap) {
+    ensure_open(&swap);
+    // After expiry the sender refunds, which leaves any premium with the recipient
+    if env.ledger().timestamp() >= swap.timelock {
+        panic!("timelock expired");
+    }
+    // A denied sender only gets its funds back through `refund` after expiry
+    denylist::ensure_allowed(env, &swap.sender);
+
+    // Transfer tokens back to sender
+    let token_client = token::Client::new(env, &swap.token);
+    token_client.transfer(&env.current_contract_address(), &swap.sender, &swap.amount);
+
+    // Mark as cancelled
+    let mut updated_swap = swap;
+    updated_swap.is_cancelled = true;
+    close_swap(env, swap_id, &updated_swap);
+
+    // Emit swap cancelled event
+    events::swap_cancelled(
+        env,
+        swap_id,
+        &updated_swap.hashlock,
+        SwapCancelled {
+            seq: events::next_seq(env),
+            sender: updated_swap.sender,
+            recipient: updated_swap.recipient,
+            token: updated_swap.token,
+            amount: updated_swap.amount,
+        },
+    );
+}
+
+/// Return an expired swap's funds to its sender and emit the refunded event
+fn refund_swap(env: &Env, swap_id: &Bytes, swap: HTLCSwap) {
+    // Transfer tokens back to sender
+    let token_client = token::Client::new(env, &swap.token);
+    token_client.transfer(&env.current_contract_address(), &swap.sender, &swap.amount);
+    
+    // Mark as refunded
+    let mut updated_swap = swap;
+    updated_swap.is_refunded = true;
+    close_swap(env, swap_id, &updated_swap);
+    
+    // Emit swap refunded event
+    events::swap_refunded(
+        env,
+        swap_id,
+        &updated_swap.hashlock,
+        SwapRefunded {
+            seq: events::next_seq(env),
+            sender: updated_swap.sender,
+            recipient: updated_swap.recipient,
+            token: updated_swap.token,
+            amount: updated_swap.amount,
+        },
+    );
+}
+
+fn get_locked_total(env: &Env, token: &Address) -> i128 {
+    env.storage().persistent().get(&DataKey::LockedTotal(token.clone())).unwrap_or(0)
+}
+
+fn set_locked_total(env: &Env, token: &Address, total: i128) {
+    let key = DataKey::LockedTotal(token.clone());
+    env.storage().persistent().set(&key, &total);
+    bump_persistent(env, &key);
+}
+
+fn reconcile(env: &Env, token: &Address) -> TokenReconciliation {
+    let balance = token::Client::new(env, token).balance(&env.current_contract_address());
+    let locked = get_locked_total(env, token);
+    TokenReconciliation {
+        token: token.clone(),
+        balance,
+        locked,
+        surplus: balance - locked,
+    }
 }
 
 #[contract]
@@ -34,6 +556,14 @@ pub struct HTLCContract;
 
 #[contractimpl]
 impl HTLCContract {
+    /// Initialize the contract with its admin
+    /// 
+    /// # Arguments
+    /// * `admin` - Initial owner, also granted every other role
+    pub fn __constructor(env: Env, admin: Address) {
+        roles::init(&env, &admin);
+    }
+
     /// Create a new HTLC swap
     /// 
     /// # Arguments
@@ -53,50 +583,115 @@ impl HTLCContract {
         amount: i128,
         hashlock: Bytes,
         timelock: u64,
+    ) {
+        let options = SwapOptions::none(&env);
+        Self::create_swap_with_options(
+            env, swap_id, sender, recipient, token, amount, hashlock, timelock, options,
+        );
+    }
+
+    /// Create a new HTLC swap with optional features
+    /// 
+    /// # Arguments
+    /// * `swap_id` - Unique identifier for the swap
+    /// * `sender` - Address that locks the funds
+    /// * `recipient` - Address that can claim the funds with preimage
+    /// * `token` - Token contract address
+    /// * `amount` - Amount of tokens to lock
+    /// * `hashlock` - SHA256 hash of the preimage (32 bytes)
+    /// * `timelock` - Unix timestamp when refund becomes possible
+    /// * `options` - Optional features, see `SwapOptions`
+    pub fn create_swap_with_options(
+        env: Env,
+        swap_id: Bytes,
+        sender: Address,
+        recipient: Address,
+        token: Address,
+        amount: i128,
+        hashlock: Bytes,
+        timelock: u64,
+        options: SwapOptions,
     ) {
         // Ensure sender authorized this call
         sender.require_auth();
-        
-        // Validate inputs
-        if amount <= 0 {
-            panic!("amount must be positive");
-        }
-        if hashlock.len() != 32 {
-            panic!("hashlock must be 32 bytes (SHA256)");
-        }
-        if timelock <= env.ledger().timestamp() {
-            panic!("timelock must be in the future");
-        }
-        
-        // Check if swap_id already exists
-        if env.storage().instance().has(&DataKey::Swap(swap_id.clone())) {
-            panic!("swap_id already exists");
-        }
-        
-        // Transfer tokens from sender to contract
-        let token_client = token::Client::new(&env, &token);
-        token_client.transfer(&sender, &env.current_contract_address(), &amount);
-        
-        // Create the swap
-        let swap = HTLCSwap {
-            sender: sender.clone(),
-            recipient: recipient.clone(),
-            token: token.clone(),
+
+        open_swap(&env, swap_id, sender, recipient, token, amount, hashlock, timelock, options, None, false, false);
+    }
+
+    /// Create a swap backed by a committee attestation of its EVM counterpart
+    /// 
+    /// Required instead of `create_swap` while an attestation committee is set.
+    /// The committee attests the funding for this swap id, sender and recipient.
+    /// Each EVM funding can back a single swap, and the swap must expire before it.
+    /// 
+    /// # Arguments
+    /// * `swap_id` - Unique identifier for the swap
+    /// * `sender` - Address that locks the funds
+    /// * `recipient` - Address that can claim the funds with preimage
+    /// * `token` - Token contract address
+    /// * `amount` - Amount of tokens to lock
+    /// * `hashlock` - SHA256 hash of the preimage (32 bytes)
+    /// * `timelock` - Unix timestamp when refund becomes possible
+    /// * `options` - Optional features, see `SwapOptions`
+    /// * `funding` - Fields of the EVM `Funded` event
+    /// * `attestations` - Signatures over `get_funding_digest(funding, swap_id, sender, recipient)`, ordered by signer index
+    pub fn create_attested_swap(
+        env: Env,
+        swap_id: Bytes,
+        sender: Address,
+        recipient: Address,
+        token: Address,
+        amount: i128,
+        hashlock: Bytes,
+        timelock: u64,
+        options: SwapOptions,
+        funding: EvmFunding,
+        attestations: Vec<Attestation>,
+    ) {
+        // Ensure sender authorized this call
+        sender.require_auth();
+
+        attestation::verify_funding(
+            &env,
+            &funding,
+            &attestations,
+            &swap_id,
+            &sender,
+            &recipient,
+            &token,
             amount,
-            hashlock: hashlock.clone(),
+            &hashlock,
             timelock,
-            preimage: None,
-            is_withdrawn: false,
-            is_refunded: false,
-        };
-        
-        // Store the swap
-        env.storage().instance().set(&DataKey::Swap(swap_id.clone()), &swap);
-        
-        // Emit swap created event
-        env.events().publish(
-            (SWAP_CREATED, symbol_short!("swap")),
-            (swap_id, sender, recipient, token, amount, hashlock, timelock)
+        );
+
+        let key = DataKey::SwapFunding(swap_id.clone());
+        open_swap(
+            &env,
+            swap_id.clone(),
+            sender,
+            recipient,
+            token,
+            amount,
+            hashlock.clone(),
+            timelock,
+            options,
+            None,
+            false,
+            true,
+        );
+        env.storage().persistent().set(&key, &funding);
+        bump_persistent(&env, &key);
+
+        events::funding_attested(
+            &env,
+            &swap_id,
+            &hashlock,
+            FundingAttested {
+                seq: events::next_seq(&env),
+                chain_id: funding.chain_id,
+                contract: funding.contract,
+                evm_swap_id: funding.swap_id,
+            },
         );
     }
 
@@ -110,125 +705,1251 @@ impl HTLCContract {
         // Ensure recipient authorized this call
         recipient.require_auth();
         
-        let swap: HTLCSwap = env.storage().instance().get(&DataKey::Swap(swap_id.clone()))
-            .expect("swap not found");
+        let swap = load_claimable_swap(&env, &swap_id, &recipient, &preimage);
         
-        // Check if already withdrawn or refunded
-        if swap.is_withdrawn {
-            panic!("swap already withdrawn");
-        }
-        if swap.is_refunded {
-            panic!("swap already refunded");
-        }
-        
-        // Verify recipient
-        if swap.recipient != recipient {
-            panic!("unauthorized recipient");
-        }
-        
-        // Verify preimage matches hashlock
-        let computed_hash = env.crypto().sha256(&preimage);
-        let computed_bytes = Bytes::from_slice(&env, &computed_hash.to_array());
-        if computed_bytes != swap.hashlock {
-            panic!("invalid preimage");
+        pay_withdrawal(&env, &swap_id, &swap, &recipient);
+        complete_withdraw(&env, &swap_id, swap, recipient, preimage);
+    }
+
+    /// Withdraw a swap to its recipient using a preimage already in the registry
+    /// 
+    /// Anyone may call this once the swap's preimage has been revealed, either by
+    /// another withdraw with the same hashlock or through `reveal`.
+    /// 
+    /// # Arguments
+    /// * `swap_id` - Unique identifier for the swap
+    pub fn withdraw_revealed(env: Env, swap_id: Bytes) {
+        let swap = load_swap(&env, &swap_id);
+        ensure_open(&swap);
+        if swap.resolver_claims {
+            panic!("swap restricted to resolvers");
         }
-        
-        // Check timelock hasn't expired
+
+        let preimage: Bytes = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Preimage(swap.hashlock.clone()))
+            .expect("preimage not revealed");
+        ensure_claimable(&env, &swap, &preimage);
+
+        let recipient = swap.recipient.clone();
+        pay_withdrawal(&env, &swap_id, &swap, &recipient);
+        complete_withdraw(&env, &swap_id, swap, recipient, preimage);
+    }
+
+    /// Withdraw a swap to its recipient on the recipient's behalf
+    /// 
+    /// Any bonded resolver may call this; it is the only way besides `withdraw`
+    /// to claim swaps created with `resolver_claims`.
+    /// 
+    /// # Arguments
+    /// * `swap_id` - Unique identifier for the swap
+    /// * `resolver` - Bonded resolver submitting the preimage
+    /// * `preimage` - The secret that hashes to the hashlock
+    pub fn resolver_withdraw(env: Env, swap_id: Bytes, resolver: Address, preimage: Bytes) {
+        resolver.require_auth();
+        resolver::ensure_bonded(&env, &resolver);
+        denylist::ensure_allowed(&env, &resolver);
+
+        let swap = load_swap(&env, &swap_id);
+        ensure_open(&swap);
+        ensure_claimable(&env, &swap, &preimage);
+
+        let recipient = swap.recipient.clone();
+        pay_withdrawal(&env, &swap_id, &swap, &recipient);
+        complete_withdraw(&env, &swap_id, swap, recipient, preimage);
+    }
+
+    /// Approve the release of a swap as one of its approvers
+    /// 
+    /// # Arguments
+    /// * `swap_id` - Unique identifier for the swap
+    /// * `approver` - Member of the swap's approver set
+    pub fn approve(env: Env, swap_id: Bytes, approver: Address) {
+        // Ensure approver authorized this call
+        approver.require_auth();
+
+        let swap = load_swap(&env, &swap_id);
+        ensure_open(&swap);
         if env.ledger().timestamp() >= swap.timelock {
             panic!("timelock expired");
         }
-        
-        // Transfer tokens to recipient
-        let token_client = token::Client::new(&env, &swap.token);
-        token_client.transfer(&env.current_contract_address(), &recipient, &swap.amount);
-        
-        // Mark as withdrawn
-        let mut updated_swap = swap;
-        updated_swap.is_withdrawn = true;
-        updated_swap.preimage = Some(preimage.clone());
-        env.storage().instance().set(&DataKey::Swap(swap_id.clone()), &updated_swap);
-        
-        // Emit swap withdrawn event
-        env.events().publish(
-            (SWAP_WITHDRAWN, symbol_short!("withdraw")),
-            (swap_id, updated_swap.sender, recipient, updated_swap.token, updated_swap.amount, preimage)
-        );
+
+        approval::approve(&env, &swap_id, &swap, &approver);
     }
 
-    /// Refund funds to sender after timelock expires
+    /// Withdraw a swap created with approvers to its recipient
+    /// 
+    /// Anyone may call this once the approvals recorded with `approve` and those
+    /// of `signers`, who must all authorize this call, reach the threshold.
     /// 
     /// # Arguments
     /// * `swap_id` - Unique identifier for the swap
-    /// * `sender` - Address that originally locked the funds
-    pub fn refund(env: Env, swap_id: Bytes, sender: Address) {
-        // Ensure sender authorized this call
-        sender.require_auth();
-        
-        let swap: HTLCSwap = env.storage().instance().get(&DataKey::Swap(swap_id.clone()))
-            .expect("swap not found");
-        
-        // Check if already withdrawn or refunded
-        if swap.is_withdrawn {
-            panic!("swap already withdrawn");
-        }
-        if swap.is_refunded {
-            panic!("swap already refunded");
-        }
-        
-        // Verify sender
-        if swap.sender != sender {
-            panic!("unauthorized sender");
+    /// * `signers` - Approvers signing this invocation; may be empty
+    /// * `preimage` - The secret that hashes to the hashlock
+    pub fn withdraw_approved(env: Env, swap_id: Bytes, signers: Vec<Address>, preimage: Bytes) {
+        for signer in signers.iter() {
+            signer.require_auth();
         }
-        
-        // Check timelock has expired
-        if env.ledger().timestamp() < swap.timelock {
-            panic!("timelock not expired yet");
+
+        let swap = load_swap(&env, &swap_id);
+        ensure_open(&swap);
+        if swap.approvers.is_empty() {
+            panic!("swap has no approvers");
         }
-        
-        // Transfer tokens back to sender
-        let token_client = token::Client::new(&env, &swap.token);
-        token_client.transfer(&env.current_contract_address(), &sender, &swap.amount);
-        
-        // Mark as refunded
-        let mut updated_swap = swap;
-        updated_swap.is_refunded = true;
-        env.storage().instance().set(&DataKey::Swap(swap_id.clone()), &updated_swap);
-        
-        // Emit swap refunded event
-        env.events().publish(
-            (SWAP_REFUNDED, symbol_short!("refund")),
-            (swap_id, sender, updated_swap.recipient, updated_swap.token, updated_swap.amount)
-        );
+        ensure_unlocked(&env, &swap, &preimage);
+        approval::ensure_approved(&env, &swap_id, &swap, &signers);
+
+        let recipient = swap.recipient.clone();
+        pay_withdrawal(&env, &swap_id, &swap, &recipient);
+        complete_withdraw(&env, &swap_id, swap, recipient, preimage);
     }
 
-    /// Get the swap details for a specific swap_id
+    /// Get the approvers who approved a swap so far
     /// 
     /// # Arguments
     /// * `swap_id` - Unique identifier for the swap
-    pub fn get_swap(env: Env, swap_id: Bytes) -> Option<HTLCSwap> {
-        env.storage().instance().get(&DataKey::Swap(swap_id))
+    pub fn get_approvals(env: Env, swap_id: Bytes) -> Vec<Address> {
+        approval::get(&env, &swap_id)
     }
 
-    /// Check if a preimage is valid for a specific swap
+    /// Publish a preimage to the registry so swaps locked by its hash can be
+    /// claimed with `withdraw_revealed`
     /// 
     /// # Arguments
-    /// * `swap_id` - Unique identifier for the swap
-    /// * `preimage` - The preimage to verify
-    pub fn verify_preimage(env: Env, swap_id: Bytes, preimage: Bytes) -> bool {
-        let swap: HTLCSwap = env.storage().instance().get(&DataKey::Swap(swap_id))
-            .expect("swap not found");
-        
-        let computed_hash = env.crypto().sha256(&preimage);
-        let computed_bytes = Bytes::from_slice(&env, &computed_hash.to_array());
-        computed_bytes == swap.hashlock
+    /// * `preimage` - The secret, e.g. as revealed on the EVM chain
+    /// 
+    /// Returns the hashlock of the preimage.
+    pub fn reveal(env: Env, preimage: Bytes) -> Bytes {
+        let hashlock = Bytes::from_slice(&env, &env.crypto().sha256(&preimage).to_array());
+
+        if record_preimage(&env, &hashlock, &preimage) {
+            events::preimage_revealed(
+                &env,
+                &hashlock,
+                PreimageRevealed {
+                    seq: events::next_seq(&env),
+                    preimage,
+                },
+            );
+        }
+
+        hashlock
     }
 
-    /// Check if a swap exists
+    /// Get the revealed preimage for a hashlock
+    /// 
+    /// # Arguments
+    /// * `hashlock` - SHA256 hash of the preimage (32 bytes)
+    pub fn get_preimage(env: Env, hashlock: Bytes) -> Option<Bytes> {
+        env.storage().persistent().get(&DataKey::Preimage(hashlock))
+    }
+
+    /// Withdraw funds using the preimage and swap them through the configured router
+    /// 
+    /// The proceeds are sent to the recipient, which then sells them on the router
+    /// along `path`; the call reverts unless the recipient ends up with at least
+    /// `min_out` of the last token in `path`. Not available for swaps with a payout
+    /// split or a callback.
     /// 
     /// # Arguments
     /// * `swap_id` - Unique identifier for the swap
-    pub fn swap_exists(env: Env, swap_id: Bytes) -> bool {
-        env.storage().instance().has(&DataKey::Swap(swap_id))
+    /// * `recipient` - Address claiming the funds (must match swap recipient)
+    /// * `preimage` - The preimage that hashes to the hashlock
+    /// * `router` - Router contract (must match `get_router`)
+    /// * `path` - Tokens to route through, starting with the swap token
+    /// * `min_out` - Minimum amount of the last token in `path` to receive
+    /// 
+    /// Returns the amount of the output token received.
+    pub fn withdraw_and_swap(
+        env: Env,
+        swap_id: Bytes,
+        recipient: Address,
+        preimage: Bytes,
+        router: Address,
+        path: Vec<Address>,
+        min_out: i128,
+    ) -> i128 {
+        // Ensure recipient authorized this call
+        recipient.require_auth();
+
+        let swap = load_claimable_swap(&env, &swap_id, &recipient, &preimage);
+        if swap.callback.is_some() || !swap.payouts.is_empty() {
+            panic!("swap has a custom payout");
+        }
+        router::validate(&env, &router, &swap.token, &path, min_out);
+
+        let fee = fees::pay_integrator(&env, &swap_id, &swap);
+        let amount_in = swap.amount - fee;
+        let token_client = token::Client::new(&env, &swap.token);
+        token_client.transfer(&env.current_contract_address(), &recipient, &amount_in);
+        let amount_out = router::swap(&env, &router, &path, &recipient, amount_in, min_out);
+
+        let hashlock = swap.hashlock.clone();
+        complete_withdraw(&env, &swap_id, swap, recipient, preimage);
+        events::withdraw_swapped(
+            &env,
+            &swap_id,
+            &hashlock,
+            WithdrawSwapped {
+                seq: events::next_seq(&env),
+                router,
+                token_out: path.last_unchecked(),
+                amount_in,
+                amount_out,
+            },
+        );
+
+        amount_out
+    }
+
+    /// Create several swaps that share one hashlock and timelock
+    /// 
+    /// Every leg is a regular swap under its own `swap_id`; `withdraw_group`
+    /// releases all of them with one preimage and `refund_group` returns them
+    /// after expiry. Not available while an attestation committee is set.
+    /// 
+    /// # Arguments
+    /// * `group_id` - Unique identifier for the group
+    /// * `sender` - Address that locks the funds of every leg
+    /// * `hashlock` - SHA256 hash of the preimage (32 bytes)
+    /// * `timelock` - Unix timestamp when refund becomes possible
+    /// * `legs` - Between 1 and 10 legs
+    pub fn create_swap_group(
+        env: Env,
+        group_id: Bytes,
+        sender: Address,
+        hashlock: Bytes,
+        timelock: u64,
+        legs: Vec<GroupLeg>,
+    ) {
+        // Ensure sender authorized this call
+        sender.require_auth();
+
+        group::validate_legs(&legs);
+        if group::get(&env, &group_id).is_some() {
+            panic!("group_id already exists");
+        }
+
+        let mut swap_ids = Vec::new(&env);
+        for leg in legs.iter() {
+            open_swap(
+                &env,
+                leg.swap_id.clone(),
+                sender.clone(),
+                leg.recipient,
+                leg.token,
+                leg.amount,
+                hashlock.clone(),
+                timelock,
+                SwapOptions::none(&env),
+                Some(group_id.clone()),
+                false,
+                false,
+            );
+            swap_ids.push_back(leg.swap_id);
+        }
+
+        let group = SwapGroup { sender, hashlock, timelock, swap_ids };
+        group::store(&env, &group_id, &group);
+
+        events::swap_group_created(
+            &env,
+            &group_id,
+            &group.hashlock,
+            SwapGroupCreated {
+                seq: events::next_seq(&env),
+                sender: group.sender,
+                timelock: group.timelock,
+                swap_ids: group.swap_ids,
+            },
+        );
+    }
+
+    /// Withdraw every open leg of a group to its recipient using the preimage
+    /// 
+    /// Anyone holding the preimage may call this; funds only go to the legs'
+    /// recipients. Legs already resolved on their own are skipped.
+    /// 
+    /// # Arguments
+    /// * `group_id` - Unique identifier for the group
+    /// * `preimage` - The preimage that hashes to the group hashlock
+    pub fn withdraw_group(env: Env, group_id: Bytes, preimage: Bytes) {
+        let group = group::load(&env, &group_id);
+
+        let mut released = 0;
+        for swap_id in group.swap_ids.iter() {
+            let swap = load_swap(&env, &swap_id);
+            if is_resolved(&swap) {
+                continue;
+            }
+            ensure_claimable(&env, &swap, &preimage);
+
+            let recipient = swap.recipient.clone();
+            pay_withdrawal(&env, &swap_id, &swap, &recipient);
+            complete_withdraw(&env, &swap_id, swap, recipient, preimage.clone());
+            released += 1;
+        }
+
+        if released == 0 {
+            panic!("no open legs in group");
+        }
+    }
+
+    /// Refund every open leg of a group to the sender after the timelock
+    /// 
+    /// # Arguments
+    /// * `group_id` - Unique identifier for the group
+    pub fn refund_group(env: Env, group_id: Bytes) {
+        let group = group::load(&env, &group_id);
+
+        // Ensure sender authorized this call
+        group.sender.require_auth();
+
+        let mut refunded = 0;
+        for swap_id in group.swap_ids.iter() {
+            let swap = load_swap(&env, &swap_id);
+            if is_resolved(&swap) {
+                continue;
+            }
+            if env.ledger().timestamp() < swap.timelock {
+                panic!("timelock not expired yet");
+            }
+
+            refund_swap(&env, &swap_id, swap);
+            refunded += 1;
+        }
+
+        if refunded == 0 {
+            panic!("no open legs in group");
+        }
+    }
+
+    /// Get the details of a swap group
+    /// 
+    /// # Arguments
+    /// * `group_id` - Unique identifier for the group
+    pub fn get_group(env: Env, group_id: Bytes) -> Option<SwapGroup> {
+        group::get(&env, &group_id)
+    }
+
+    /// Post a Dutch-auction order, escrowing the maker's tokens until a resolver
+    /// fills it or the maker cancels it
+    /// 
+    /// Not available while an attestation committee is set, since orders couldn't
+    /// be filled.
+    /// 
+    /// # Arguments
+    /// * `order_id` - Unique identifier for the order
+    /// * `maker` - Address selling `amount` of `token`
+    /// * `token` - Token contract address
+    /// * `amount` - Amount of tokens to sell
+    /// * `hashlock` - SHA256 hash of the maker's preimage (32 bytes)
+    /// * `auction` - Rate the filler owes on the destination chain, decaying over time
+    /// * `swap_duration` - Timelock of the filled swap, in seconds from the fill
+    pub fn post_order(
+        env: Env,
+        order_id: Bytes,
+        maker: Address,
+        token: Address,
+        amount: i128,
+        hashlock: Bytes,
+        auction: DutchAuction,
+        swap_duration: u64,
+    ) {
+        // Ensure maker signed the order
+        maker.require_auth();
+        ensure_not_paused(&env);
+        if attestation::committee(&env).is_some() {
+            panic!("EVM funding attestation required");
+        }
+
+        order::validate(amount, &hashlock, &auction, swap_duration);
+        if governance::is_delisted(&env, &token) {
+            panic!("token delisted");
+        }
+        denylist::ensure_allowed(&env, &maker);
+        if order::get(&env, &order_id).is_some() {
+            panic!("order_id already exists");
+        }
+
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&maker, &env.current_contract_address(), &amount);
+        set_locked_total(&env, &token, get_locked_total(&env, &token) + amount);
+
+        let order = Order {
+            maker,
+            token,
+            amount,
+            hashlock,
+            auction,
+            swap_duration,
+            swap_id: None,
+            fill_rate: 0,
+            taking_amount: 0,
+            is_cancelled: false,
+        };
+        order::store(&env, &order_id, &order);
+
+        events::order_posted(
+            &env,
+            &order_id,
+            &order.hashlock,
+            OrderPosted {
+                seq: events::next_seq(&env),
+                maker: order.maker,
+                token: order.token,
+                amount: order.amount,
+                auction: order.auction,
+                swap_duration: order.swap_duration,
+            },
+        );
+    }
+
+    /// Fill an order at the current auction rate, turning its escrow into a swap
+    /// from the maker to the resolver
+    /// 
+    /// The resolver must be bonded. Filling commits it to lock the returned amount
+    /// for the maker on the destination chain under the same hashlock. Not
+    /// available while an attestation committee is set; the maker can still cancel.
+    /// 
+    /// # Arguments
+    /// * `order_id` - Unique identifier for the order
+    /// * `resolver` - Bonded resolver filling the order
+    /// * `swap_id` - Unique identifier for the created swap
+    /// 
+    /// Returns the amount owed on the destination chain.
+    pub fn fill_order(env: Env, order_id: Bytes, resolver: Address, swap_id: Bytes) -> i128 {
+        resolver.require_auth();
+        resolver::ensure_bonded(&env, &resolver);
+
+        let mut order = order::load(&env, &order_id);
+        order::ensure_open(&order);
+        if env.ledger().timestamp() < order.auction.start {
+            panic!("auction not started");
+        }
+
+        let rate = order::current_rate(&env, &order.auction);
+        let taking_amount = order::taking_amount(order.amount, rate);
+        let timelock = env.ledger().timestamp() + order.swap_duration;
+
+        open_swap(
+            &env,
+            swap_id.clone(),
+            order.maker.clone(),
+            resolver.clone(),
+            order.token.clone(),
+            order.amount,
+            order.hashlock.clone(),
+            timelock,
+            SwapOptions::none(&env),
+            None,
+            true,
+            false,
+        );
+
+        order.swap_id = Some(swap_id.clone());
+        order.fill_rate = rate;
+        order.taking_amount = taking_amount;
+        order::store(&env, &order_id, &order);
+
+        events::order_filled(
+            &env,
+            &order_id,
+            &order.hashlock,
+            OrderFilled {
+                seq: events::next_seq(&env),
+                resolver,
+                swap_id,
+                rate,
+                taking_amount,
+            },
+        );
+
+        taking_amount
+    }
+
+    /// Cancel an unfilled order and return the escrow to its maker
+    /// 
+    /// # Arguments
+    /// * `order_id` - Unique identifier for the order
+    pub fn cancel_order(env: Env, order_id: Bytes) {
+        let mut order = order::load(&env, &order_id);
+        order.maker.require_auth();
+        order::ensure_open(&order);
+
+        let token_client = token::Client::new(&env, &order.token);
+        token_client.transfer(&env.current_contract_address(), &order.maker, &order.amount);
+        set_locked_total(&env, &order.token, get_locked_total(&env, &order.token) - order.amount);
+
+        order.is_cancelled = true;
+        order::store(&env, &order_id, &order);
+
+        events::order_cancelled(
+            &env,
+            &order_id,
+            &order.hashlock,
+            OrderCancelled {
+                seq: events::next_seq(&env),
+                maker: order.maker,
+                amount: order.amount,
+            },
+        );
+    }
+
+    /// Get the details of an order
+    /// 
+    /// # Arguments
+    /// * `order_id` - Unique identifier for the order
+    pub fn get_order(env: Env, order_id: Bytes) -> Option<Order> {
+        order::get(&env, &order_id)
+    }
+
+    /// Get the rate an order would be filled at now
+    /// 
+    /// # Arguments
+    /// * `order_id` - Unique identifier for the order
+    pub fn get_order_rate(env: Env, order_id: Bytes) -> i128 {
+        order::current_rate(&env, &order::load(&env, &order_id).auction)
+    }
+
+    /// Refund funds to sender after timelock expires
+    /// 
+    /// # Arguments
+    /// * `swap_id` - Unique identifier for the swap
+    /// * `sender` - Address that originally locked the funds
+    pub fn refund(env: Env, swap_id: Bytes, sender: Address) {
+        // Ensure sender authorized this call
+        sender.require_auth();
+        
+        let swap = load_swap(&env, &swap_id);
+        
+        // Check if already withdrawn, refunded or cancelled
+        ensure_open(&swap);
+        
+        // Verify sender
+        if swap.sender != sender {
+            panic!("unauthorized sender");
+        }
+        
+        // Check timelock has expired
+        if env.ledger().timestamp() < swap.timelock {
+            panic!("timelock not expired yet");
+        }
+        
+        refund_swap(&env, &swap_id, swap);
+    }
+
+    /// Return the funds to the sender before the timelock
+    /// 
+    /// Requires the recipient's authorization: the recipient declining its claim is
+    /// enough to release the funds, and a mutual cancellation is the recipient
+    /// signing alongside the sender. Swaps with approvers use `cancel_approved`.
+    /// 
+    /// # Arguments
+    /// * `swap_id` - Unique identifier for the swap
+    pub fn cancel(env: Env, swap_id: Bytes) {
+        let swap = load_swap(&env, &swap_id);
+
+        // Ensure recipient gave up its claim
+        swap.recipient.require_auth();
+
+        if !swap.approvers.is_empty() {
+            panic!("swap requires approvals");
+        }
+        cancel_swap(&env, &swap_id, swap);
+    }
+
+    /// Return the funds of a swap created with approvers to the sender before the
+    /// timelock
+    /// 
+    /// `signers`, who must all authorize this call, have to reach the approval
+    /// threshold on their own; approvals recorded with `approve` don't count.
+    /// 
+    /// # Arguments
+    /// * `swap_id` - Unique identifier for the swap
+    /// * `signers` - Approvers agreeing to the cancellation
+    pub fn cancel_approved(env: Env, swap_id: Bytes, signers: Vec<Address>) {
+        for signer in signers.iter() {
+            signer.require_auth();
+        }
+
+        let swap = load_swap(&env, &swap_id);
+        if swap.approvers.is_empty() {
+            panic!("swap has no approvers");
+        }
+        approval::ensure_signed(&env, &swap, &signers);
+        cancel_swap(&env, &swap_id, swap);
+    }
+
+    /// Resolve a dispute over an escrow swap before its timelock
+    /// 
+    /// Requires the arbiter's authorization. Releasing pays the whole amount to
+    /// the recipient without the preimage; otherwise it goes back to the sender.
+    /// 
+    /// # Arguments
+    /// * `swap_id` - Unique identifier for the swap
+    /// * `release` - Whether the recipient gets the funds
+    pub fn arbitrate(env: Env, swap_id: Bytes, release: bool) {
+        let swap = load_swap(&env, &swap_id);
+        let arbiter = swap.arbiter.clone().expect("swap has no arbiter");
+
+        // Ensure arbiter authorized this call
+        arbiter.require_auth();
+
+        ensure_open(&swap);
+        if env.ledger().timestamp() >= swap.timelock {
+            panic!("timelock expired");
+        }
+
+        let recipient_amount = if release { swap.amount } else { 0 };
+        escrow::settle(&env, &swap_id, swap, recipient_amount, Some(arbiter));
+    }
+
+    /// Split an escrow swap by agreement of both parties, without the arbiter
+    /// 
+    /// Requires the sender's and the recipient's authorization, and works until
+    /// the swap is closed, even after the timelock.
+    /// 
+    /// # Arguments
+    /// * `swap_id` - Unique identifier for the swap
+    /// * `recipient_amount` - Part of the amount paid to the recipient; the rest returns to the sender
+    pub fn settle(env: Env, swap_id: Bytes, recipient_amount: i128) {
+        let swap = load_swap(&env, &swap_id);
+        if swap.arbiter.is_none() {
+            panic!("swap has no arbiter");
+        }
+
+        // Ensure both parties agreed to the split
+        swap.sender.require_auth();
+        swap.recipient.require_auth();
+
+        ensure_open(&swap);
+        escrow::settle(&env, &swap_id, swap, recipient_amount, None);
+    }
+
+    /// Push back the timelock of an open swap
+    /// 
+    /// Requires the authorization of both parties: a later timelock delays the
+    /// sender's refund and keeps the recipient's claim open. The new timelock may
+    /// be at most `get_max_timelock_horizon` seconds past the swap's initial
+    /// timelock, however often it is extended, and an attested swap must still
+    /// expire before its EVM counterpart. Legs of a swap group keep the group's
+    /// timelock.
+    /// 
+    /// # Arguments
+    /// * `swap_id` - Unique identifier for the swap
+    /// * `new_timelock` - New Unix timestamp when refund becomes possible
+    pub fn extend_timelock(env: Env, swap_id: Bytes, new_timelock: u64) {
+        let swap = load_swap(&env, &swap_id);
+
+        // Ensure both parties consented to the delay
+        swap.sender.require_auth();
+        swap.recipient.require_auth();
+
+        ensure_open(&swap);
+        if swap.group_id.is_some() {
+            panic!("group legs share the group timelock");
+        }
+
+        let now = env.ledger().timestamp();
+        if now >= swap.timelock {
+            panic!("timelock expired");
+        }
+        if new_timelock <= swap.timelock {
+            panic!("new timelock must be later than current timelock");
+        }
+        if new_timelock > swap.initial_timelock + Self::get_max_timelock_horizon(env.clone()) {
+            panic!("new timelock exceeds maximum horizon");
+        }
+
+        let old_timelock = swap.timelock;
+        let mut updated_swap = swap;
+        updated_swap.timelock = new_timelock;
+        attestation::ensure_still_backed(&env, &swap_id, &updated_swap);
+        if updated_swap.resolver_sender {
+            resolver::expose(&env, &updated_swap.sender, new_timelock);
+        }
+        store_swap(&env, &swap_id, &updated_swap);
+
+        // Emit timelock extended event
+        events::timelock_extended(
+            &env,
+            &swap_id,
+            &updated_swap.hashlock,
+            TimelockExtended {
+                seq: events::next_seq(&env),
+                old_timelock,
+                new_timelock,
+            },
+        );
+    }
+
+    /// Add funds to an open swap before its timelock
+    /// 
+    /// An attested swap can only grow as far as its EVM funding covers.
+    /// 
+    /// # Arguments
+    /// * `swap_id` - Unique identifier for the swap
+    /// * `extra_amount` - Amount of tokens to add to the swap
+    pub fn top_up(env: Env, swap_id: Bytes, extra_amount: i128) {
+        let swap = load_swap(&env, &swap_id);
+
+        // Ensure sender authorized this call
+        swap.sender.require_auth();
+
+        if extra_amount <= 0 {
+            panic!("amount must be positive");
+        }
+        ensure_open(&swap);
+        denylist::ensure_allowed(&env, &swap.sender);
+        if env.ledger().timestamp() >= swap.timelock {
+            panic!("timelock expired");
+        }
+
+        // Transfer the extra tokens from sender to contract
+        let token_client = token::Client::new(&env, &swap.token);
+        token_client.transfer(&swap.sender, &env.current_contract_address(), &extra_amount);
+        set_locked_total(&env, &swap.token, get_locked_total(&env, &swap.token) + extra_amount);
+        stats::record_locked(&env, &swap.sender, &swap.token, extra_amount, false);
+
+        let mut updated_swap = swap;
+        updated_swap.amount += extra_amount;
+        attestation::ensure_still_backed(&env, &swap_id, &updated_swap);
+        store_swap(&env, &swap_id, &updated_swap);
+
+        // Emit swap topped up event
+        events::swap_topped_up(
+            &env,
+            &swap_id,
+            &updated_swap.hashlock,
+            SwapToppedUp {
+                seq: events::next_seq(&env),
+                extra_amount,
+                amount: updated_swap.amount,
+            },
+        );
+    }
+
+    /// Get the swap details for a specific swap_id
+    /// 
+    /// # Arguments
+    /// * `swap_id` - Unique identifier for the swap
+    pub fn get_swap(env: Env, swap_id: Bytes) -> Option<HTLCSwap> {
+        get_swap(&env, &swap_id)
+    }
+
+    /// Check if a preimage is valid for a specific swap
+    /// 
+    /// # Arguments
+    /// * `swap_id` - Unique identifier for the swap
+    /// * `preimage` - The preimage to verify
+    pub fn verify_preimage(env: Env, swap_id: Bytes, preimage: Bytes) -> bool {
+        let swap = load_swap(&env, &swap_id);
+        
+        let computed_hash = env.crypto().sha256(&preimage);
+        let computed_bytes = Bytes::from_slice(&env, &computed_hash.to_array());
+        computed_bytes == swap.hashlock
+    }
+
+    /// Check if a swap exists
+    /// 
+    /// # Arguments
+    /// * `swap_id` - Unique identifier for the swap
+    pub fn swap_exists(env: Env, swap_id: Bytes) -> bool {
+        env.storage().persistent().has(&DataKey::Swap(swap_id))
+    }
+
+    /// List swap ids created by a sender, oldest first
+    /// 
+    /// Swaps stay listed after they close; use `get_swap` for their state.
+    /// 
+    /// # Arguments
+    /// * `sender` - Address that locked the funds
+    /// * `cursor` - Position of the first entry to return (0 for the first page)
+    /// * `limit` - Maximum number of entries to return (capped at 30)
+    pub fn get_swaps_by_sender(env: Env, sender: Address, cursor: u32, limit: u32) -> Vec<Bytes> {
+        index::swaps_by_sender(&env, &sender, cursor, limit)
+    }
+
+    /// List swap ids payable to a recipient, oldest first
+    /// 
+    /// Swaps stay listed after they close; use `get_swap` for their state.
+    /// 
+    /// # Arguments
+    /// * `recipient` - Address that can claim the funds
+    /// * `cursor` - Position of the first entry to return (0 for the first page)
+    /// * `limit` - Maximum number of entries to return (capped at 30)
+    pub fn get_swaps_by_recipient(env: Env, recipient: Address, cursor: u32, limit: u32) -> Vec<Bytes> {
+        index::swaps_by_recipient(&env, &recipient, cursor, limit)
+    }
+
+    /// Get the most recently created swap_id for a hashlock
+    /// 
+    /// # Arguments
+    /// * `hashlock` - SHA256 hash of the preimage (32 bytes)
+    pub fn get_swap_id_by_hashlock(env: Env, hashlock: Bytes) -> Option<Bytes> {
+        index::swap_id_by_hashlock(&env, &hashlock)
+    }
+
+    /// Get the details of several swaps at once
+    /// 
+    /// # Arguments
+    /// * `swap_ids` - Swap identifiers (at most 30); unknown ids yield `None`
+    pub fn get_swaps(env: Env, swap_ids: Vec<Bytes>) -> Vec<Option<HTLCSwap>> {
+        if swap_ids.len() > index::MAX_PAGE_SIZE {
+            panic!("too many swap ids");
+        }
+
+        let mut swaps = Vec::new(&env);
+        for swap_id in swap_ids.iter() {
+            swaps.push_back(get_swap(&env, &swap_id));
+        }
+        swaps
+    }
+
+    /// Get the status of several swaps at once
+    /// 
+    /// # Arguments
+    /// * `swap_ids` - Swap identifiers (at most 30); unknown ids yield `None`
+    pub fn get_statuses(env: Env, swap_ids: Vec<Bytes>) -> Vec<Option<SwapStatus>> {
+        if swap_ids.len() > index::MAX_PAGE_SIZE {
+            panic!("too many swap ids");
+        }
+
+        let mut statuses = Vec::new(&env);
+        for swap_id in swap_ids.iter() {
+            statuses.push_back(get_swap(&env, &swap_id).map(|swap| swap_status(&env, &swap)));
+        }
+        statuses
+    }
+
+    /// List swap ids that are not yet withdrawn, refunded, cancelled or settled
+    /// 
+    /// Order is not stable: resolving a swap moves the last open swap into its
+    /// position, so a listing taken across several calls may skip or repeat entries.
+    /// 
+    /// # Arguments
+    /// * `cursor` - Position of the first entry to return (0 for the first page)
+    /// * `limit` - Maximum number of entries to return (capped at 30)
+    pub fn get_open_swaps(env: Env, cursor: u32, limit: u32) -> Vec<Bytes> {
+        index::open_swaps(&env, cursor, limit)
+    }
+
+    /// Get the swap outcome counters and per-token volume of an address
+    /// 
+    /// # Arguments
+    /// * `participant` - Sender or recipient address
+    pub fn get_participant_stats(env: Env, participant: Address) -> ParticipantStats {
+        stats::get(&env, &participant)
+    }
+
+    /// Get the sequence number of the last event published by the contract
+    /// 
+    /// Every event carries a `seq` one greater than the previous one, so a consumer
+    /// that sees a jump knows it missed events and should resync from `get_swap`.
+    pub fn get_event_seq(env: Env) -> u64 {
+        events::last_seq(&env)
+    }
+
+    /// Get the contract owner
+    pub fn get_admin(env: Env) -> Address {
+        roles::owner(&env)
+    }
+
+    /// Check whether an address holds a role
+    /// 
+    /// # Arguments
+    /// * `role` - Role to check
+    /// * `account` - Address to check
+    pub fn has_role(env: Env, role: Role, account: Address) -> bool {
+        roles::has_role(&env, role, &account)
+    }
+
+    /// Grant a role other than `Owner`; only the owner can grant roles
+    /// 
+    /// # Arguments
+    /// * `role` - Role to grant
+    /// * `account` - Address receiving the role
+    pub fn grant_role(env: Env, role: Role, account: Address) {
+        roles::require_owner(&env);
+        roles::grant(&env, role, &account);
+    }
+
+    /// Revoke a role other than `Owner`; only the owner can revoke roles
+    /// 
+    /// # Arguments
+    /// * `role` - Role to revoke
+    /// * `account` - Address losing the role
+    pub fn revoke_role(env: Env, role: Role, account: Address) {
+        roles::require_owner(&env);
+        roles::revoke(&env, role, &account);
+    }
+
+    /// Propose a new owner, who becomes owner once it calls `accept_ownership`.
+    /// A later proposal replaces a pending one.
+    /// 
+    /// # Arguments
+    /// * `new_owner` - Proposed owner
+    pub fn propose_owner(env: Env, new_owner: Address) {
+        roles::require_owner(&env);
+        roles::propose_owner(&env, &new_owner);
+    }
+
+    /// Accept a pending ownership proposal; requires the proposed owner's authorization.
+    /// The roles the previous owner held move to the new owner.
+    pub fn accept_ownership(env: Env) {
+        roles::accept_ownership(&env);
+    }
+
+    /// Get the proposed owner awaiting `accept_ownership`
+    pub fn get_pending_owner(env: Env) -> Option<Address> {
+        roles::pending_owner(&env)
+    }
+
+    /// Block an address from creating, funding, receiving or being paid by swaps.
+    /// A denied sender can still refund its swaps after they expire.
+    /// 
+    /// # Arguments
+    /// * `account` - Address to block
+    pub fn deny(env: Env, account: Address) {
+        roles::require_owner(&env);
+        denylist::deny(&env, &account);
+    }
+
+    /// Remove an address from the denylist
+    /// 
+    /// # Arguments
+    /// * `account` - Address to unblock
+    pub fn allow(env: Env, account: Address) {
+        roles::require_owner(&env);
+        denylist::allow(&env, &account);
+    }
+
+    /// Check whether an address is on the denylist
+    /// 
+    /// # Arguments
+    /// * `account` - Address to check
+    pub fn is_denied(env: Env, account: Address) -> bool {
+        denylist::is_denied(&env, &account)
+    }
+
+    /// Stop new swaps and orders from being created. Withdrawals, refunds and
+    /// cancellations keep working.
+    /// 
+    /// # Arguments
+    /// * `caller` - Holder of the `Pauser` role
+    pub fn pause(env: Env, caller: Address) {
+        roles::require_role(&env, &caller, Role::Pauser);
+        env.storage().instance().set(&DataKey::Paused, &true);
+        events::paused(&env, PauseChanged { seq: events::next_seq(&env), by: caller });
+    }
+
+    /// Allow swap and order creation again
+    /// 
+    /// # Arguments
+    /// * `caller` - Holder of the `Pauser` role
+    pub fn unpause(env: Env, caller: Address) {
+        roles::require_role(&env, &caller, Role::Pauser);
+        env.storage().instance().set(&DataKey::Paused, &false);
+        events::unpaused(&env, PauseChanged { seq: events::next_seq(&env), by: caller });
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        env.storage().instance().get(&DataKey::Paused).unwrap_or(false)
+    }
+
+    /// Queue a configuration change, executable after `get_min_delay` seconds
+    /// 
+    /// # Arguments
+    /// * `caller` - Holder of the role the operation requires, see `Operation`
+    /// * `operation` - Change to make
+    /// 
+    /// Returns the operation id.
+    pub fn schedule_operation(env: Env, caller: Address, operation: Operation) -> u32 {
+        governance::schedule(&env, &caller, operation)
+    }
+
+    /// Apply a queued operation once its delay has passed; anyone may execute
+    /// 
+    /// # Arguments
+    /// * `id` - Operation id
+    pub fn execute_operation(env: Env, id: u32) {
+        match governance::take_ready(&env, id) {
+            Operation::SetMaxIntegratorFeeBps(bps) => fees::set_max_integrator_fee_bps(&env, bps),
+            Operation::DelistToken(token) => governance::set_delisted(&env, &token, true),
+            Operation::RelistToken(token) => governance::set_delisted(&env, &token, false),
+            Operation::Upgrade(new_wasm_hash) => env.deployer().update_current_contract_wasm(new_wasm_hash),
+            Operation::SetMinDelay(delay) => governance::set_min_delay(&env, delay),
+            Operation::SetMaxTimelockHorizon(horizon) => {
+                env.storage().instance().set(&DataKey::MaxTimelockHorizon, &horizon)
+            }
+            Operation::SetRouter(router) => env.storage().instance().set(&DataKey::Router, &router),
+            Operation::SetMinBond(amount) => resolver::set_min_bond(&env, amount),
+            Operation::SetUnbondDelay(delay) => env.storage().instance().set(&DataKey::UnbondDelay, &delay),
+            Operation::SetAttestationCommittee(committee) => {
+                env.storage().instance().set(&DataKey::AttestationCommittee, &committee)
+            }
+            Operation::RemoveAttestationCommittee => env.storage().instance().remove(&DataKey::AttestationCommittee),
+            Operation::SetFundingAsset(chain_id, contract, asset) => {
+                attestation::set_funding_asset(&env, chain_id, &contract, &asset)
+            }
+        }
+    }
+
+    /// Drop a queued operation
+    /// 
+    /// # Arguments
+    /// * `caller` - The owner or a holder of the role the operation requires
+    /// * `id` - Operation id
+    pub fn cancel_operation(env: Env, caller: Address, id: u32) {
+        governance::cancel(&env, &caller, id);
+    }
+
+    /// Get a queued operation
+    /// 
+    /// # Arguments
+    /// * `id` - Operation id
+    pub fn get_operation(env: Env, id: u32) -> Option<ScheduledOperation> {
+        governance::get(&env, id)
+    }
+
+    /// List the ids of queued operations, oldest first
+    pub fn get_pending_operations(env: Env) -> Vec<u32> {
+        governance::pending_ids(&env)
+    }
+
+    /// Get the delay between scheduling and executing an operation
+    pub fn get_min_delay(env: Env) -> u64 {
+        governance::min_delay(&env)
+    }
+
+    /// Check whether new swaps in a token are blocked
+    /// 
+    /// # Arguments
+    /// * `token` - Token contract address
+    pub fn is_token_delisted(env: Env, token: Address) -> bool {
+        governance::is_delisted(&env, &token)
+    }
+
+    /// Get how far past a swap's initial timelock, in seconds, `extend_timelock` may push it
+    pub fn get_max_timelock_horizon(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::MaxTimelockHorizon)
+            .unwrap_or(DEFAULT_MAX_TIMELOCK_HORIZON)
+    }
+
+    /// Get the highest integrator fee, in basis points, a swap may specify
+    pub fn get_max_integrator_fee_bps(env: Env) -> u32 {
+        fees::max_integrator_fee_bps(&env)
+    }
+
+    /// Get the total fees of a token paid out to an integrator
+    /// 
+    /// # Arguments
+    /// * `integrator` - Integrator address
+    /// * `token` - Token contract address
+    pub fn get_integrator_accrued(env: Env, integrator: Address, token: Address) -> i128 {
+        fees::accrued(&env, &integrator, &token)
+    }
+
+    /// Get the router `withdraw_and_swap` is allowed to use
+    pub fn get_router(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Router)
+    }
+
+    /// Register as a resolver; the resolver is bonded once it stakes at least
+    /// `get_min_bond`
+    /// 
+    /// # Arguments
+    /// * `resolver` - Address registering
+    pub fn register_resolver(env: Env, resolver: Address) {
+        resolver.require_auth();
+        resolver::register(&env, &resolver);
+    }
+
+    /// Add to a resolver's bond, paid in the bond token
+    /// 
+    /// # Arguments
+    /// * `resolver` - Registered resolver
+    /// * `amount` - Amount of the bond token to stake
+    pub fn stake(env: Env, resolver: Address, amount: i128) {
+        resolver.require_auth();
+        resolver::stake(&env, &resolver, amount);
+    }
+
+    /// Start withdrawing part of a resolver's bond. The amount stays slashable and
+    /// can be withdrawn with `withdraw_bond` after `get_unbond_delay` seconds.
+    /// 
+    /// # Arguments
+    /// * `resolver` - Registered resolver
+    /// * `amount` - Amount of the active bond to unbond
+    pub fn unbond(env: Env, resolver: Address, amount: i128) {
+        resolver.require_auth();
+        resolver::unbond(&env, &resolver, amount);
+    }
+
+    /// Withdraw a resolver's unbonded stake once the unbond delay has passed
+    /// 
+    /// The stake also stays locked until one unbond delay after the latest
+    /// timelock of the swaps the resolver funded, leaving time to slash them.
+    /// 
+    /// # Arguments
+    /// * `resolver` - Registered resolver
+    /// 
+    /// Returns the amount transferred.
+    pub fn withdraw_bond(env: Env, resolver: Address) -> i128 {
+        resolver.require_auth();
+        resolver::withdraw_bond(&env, &resolver)
+    }
+
+    /// Slash the resolver that funded a refunded or expired swap, paying the swap's
+    /// recipient
+    /// 
+    /// Only the admin can slash, after judging that the resolver failed to complete
+    /// its side of the swap. Each swap can be slashed once, and not while its
+    /// recipient is denied.
+    /// 
+    /// # Arguments
+    /// * `swap_id` - Refunded or expired swap created with `resolver_sender`
+    /// * `amount` - Maximum amount of the bond to take
+    /// 
+    /// Returns the amount slashed.
+    pub fn slash(env: Env, swap_id: Bytes, amount: i128) -> i128 {
+        roles::require_owner(&env);
+        let swap = load_swap(&env, &swap_id);
+        resolver::slash(&env, &swap_id, &swap, amount)
+    }
+
+    /// Get a resolver's bond, if registered
+    /// 
+    /// # Arguments
+    /// * `resolver` - Resolver address
+    pub fn get_resolver(env: Env, resolver: Address) -> Option<Resolver> {
+        resolver::get(&env, &resolver)
+    }
+
+    /// Check whether an address is a registered resolver with at least the minimum bond
+    /// 
+    /// # Arguments
+    /// * `resolver` - Resolver address
+    pub fn is_bonded_resolver(env: Env, resolver: Address) -> bool {
+        resolver::is_bonded(&env, &resolver)
+    }
+
+    /// Get the token resolvers stake in
+    pub fn get_bond_token(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::BondToken)
+    }
+
+    /// Set the token resolvers stake in; can only be set once
+    /// 
+    /// # Arguments
+    /// * `caller` - Holder of the `TokenManager` role
+    /// * `token` - Token contract address
+    pub fn set_bond_token(env: Env, caller: Address, token: Address) {
+        roles::require_role(&env, &caller, Role::TokenManager);
+        resolver::set_bond_token(&env, &token);
+    }
+
+    /// Get the bond a resolver needs to count as bonded
+    pub fn get_min_bond(env: Env) -> i128 {
+        resolver::min_bond(&env)
+    }
+
+    /// Get the delay between `unbond` and `withdraw_bond`
+    pub fn get_unbond_delay(env: Env) -> u64 {
+        resolver::unbond_delay(&env)
+    }
+
+    /// Get the attested EVM funding of a swap created with `create_attested_swap`
+    /// 
+    /// # Arguments
+    /// * `swap_id` - Unique identifier for the swap
+    pub fn get_swap_funding(env: Env, swap_id: Bytes) -> Option<EvmFunding> {
+        env.storage().persistent().get(&DataKey::SwapFunding(swap_id))
+    }
+
+    /// Digest the committee signs to attest an EVM funding backing a swap
+    /// 
+    /// # Arguments
+    /// * `funding` - Fields of the EVM `Funded` event
+    /// * `swap_id` - Stellar swap the funding backs
+    /// * `sender` - Address locking the funds on Stellar
+    /// * `recipient` - Address that can claim the funds on Stellar
+    pub fn get_funding_digest(
+        env: Env,
+        funding: EvmFunding,
+        swap_id: Bytes,
+        sender: Address,
+        recipient: Address,
+    ) -> BytesN<32> {
+        attestation::funding_digest(&env, &funding, &swap_id, &sender, &recipient).to_bytes()
+    }
+
+    /// Get the committee attesting EVM fundings, if attestations are required
+    pub fn get_attestation_committee(env: Env) -> Option<Committee> {
+        attestation::committee(&env)
+    }
+
+    /// Get the Stellar asset registered for an EVM HTLC contract
+    /// 
+    /// # Arguments
+    /// * `chain_id` - EVM chain of the contract
+    /// * `contract` - EVM HTLC contract
+    pub fn get_funding_asset(env: Env, chain_id: u64, contract: BytesN<20>) -> Option<FundingAsset> {
+        attestation::funding_asset(&env, chain_id, &contract)
+    }
+
+    /// Get the total amount of a token the contract owes: the amounts and premiums
+    /// of open swaps, the escrows of unfilled orders and, in the bond token, the
+    /// active and unbonding resolver bonds
+    /// 
+    /// # Arguments
+    /// * `token` - Token contract address
+    pub fn get_locked_total(env: Env, token: Address) -> i128 {
+        get_locked_total(&env, &token)
+    }
+
+    /// Compare the contract's token balance with the amount it owes, see `get_locked_total`
+    /// 
+    /// # Arguments
+    /// * `token` - Token contract address
+    pub fn reconcile(env: Env, token: Address) -> TokenReconciliation {
+        reconcile(&env, &token)
+    }
+
+    /// Recover tokens held above the contract's liabilities (e.g. accidental transfers)
+    /// 
+    /// # Arguments
+    /// * `caller` - Holder of the `TokenManager` role
+    /// * `token` - Token contract address
+    /// * `to` - Address receiving the surplus
+    /// 
+    /// Returns the amount transferred.
+    pub fn skim(env: Env, caller: Address, token: Address, to: Address) -> i128 {
+        roles::require_role(&env, &caller, Role::TokenManager);
+
+        let surplus = reconcile(&env, &token).surplus;
+        if surplus <= 0 {
+            panic!("no surplus to skim");
+        }
+
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&env.current_contract_address(), &to, &surplus);
+
+        events::tokens_skimmed(
+            &env,
+            &token,
+            TokensSkimmed {
+                seq: events::next_seq(&env),
+                to,
+                amount: surplus,
+            },
+        );
+
+        surplus
     }
 }
 
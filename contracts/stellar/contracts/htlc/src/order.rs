@@ -0,0 +1,97 @@
+use soroban_sdk::{contracttype, Address, Bytes, Env};
+
+use crate::{bump_instance, bump_persistent, DataKey};
+
+/// Rates are fixed-point numbers with 7 decimals, like Stellar amounts
+pub const RATE_SCALE: i128 = 10_000_000;
+
+/// Linear decay of the rate an order is filled at
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct DutchAuction {
+    pub start_rate: i128, // Destination units per source unit, times RATE_SCALE, at `start`
+    pub end_rate: i128,   // Floor reached at `start + duration`; at most `start_rate`
+    pub start: u64,       // Unix timestamp when the order becomes fillable
+    pub duration: u64,    // Seconds over which the rate decays
+}
+
+/// A maker's intent to swap, escrowed until a resolver fills it with an `HTLCSwap`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct Order {
+    pub maker: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub hashlock: Bytes,
+    pub auction: DutchAuction,
+    pub swap_duration: u64,       // Timelock of the filled swap, in seconds from the fill
+    pub swap_id: Option<Bytes>,   // Set once filled
+    pub fill_rate: i128,          // Auction rate at the fill, times RATE_SCALE; 0 until filled
+    pub taking_amount: i128,      // Owed by the filler on the destination chain; 0 until filled
+    pub is_cancelled: bool,
+}
+
+pub fn validate(amount: i128, hashlock: &Bytes, auction: &DutchAuction, swap_duration: u64) {
+    if amount <= 0 {
+        panic!("amount must be positive");
+    }
+    if hashlock.len() != 32 {
+        panic!("hashlock must be 32 bytes (SHA256)");
+    }
+    if auction.end_rate <= 0 {
+        panic!("rate must be positive");
+    }
+    if auction.start_rate < auction.end_rate {
+        panic!("start rate below end rate");
+    }
+    if auction.duration == 0 {
+        panic!("auction duration must be positive");
+    }
+    if swap_duration == 0 {
+        panic!("swap duration must be positive");
+    }
+}
+
+pub fn get(env: &Env, order_id: &Bytes) -> Option<Order> {
+    env.storage().persistent().get(&DataKey::Order(order_id.clone()))
+}
+
+pub fn load(env: &Env, order_id: &Bytes) -> Order {
+    get(env, order_id).expect("order not found")
+}
+
+pub fn store(env: &Env, order_id: &Bytes, order: &Order) {
+    let key = DataKey::Order(order_id.clone());
+    env.storage().persistent().set(&key, order);
+    bump_persistent(env, &key);
+    bump_instance(env);
+}
+
+pub fn ensure_open(order: &Order) {
+    if order.swap_id.is_some() {
+        panic!("order already filled");
+    }
+    if order.is_cancelled {
+        panic!("order already cancelled");
+    }
+}
+
+/// Rate of the auction at the current ledger time
+pub fn current_rate(env: &Env, auction: &DutchAuction) -> i128 {
+    let now = env.ledger().timestamp();
+    if now <= auction.start {
+        return auction.start_rate;
+    }
+    let elapsed = now - auction.start;
+    if elapsed >= auction.duration {
+        return auction.end_rate;
+    }
+
+    let decay = (auction.start_rate - auction.end_rate) * elapsed as i128 / auction.duration as i128;
+    auction.start_rate - decay
+}
+
+/// Amount the filler owes on the destination chain at `rate`
+pub fn taking_amount(amount: i128, rate: i128) -> i128 {
+    amount * rate / RATE_SCALE
+}
@@ -0,0 +1,66 @@
+use soroban_sdk::{contracttype, token, Address, Env, Vec};
+
+/// Basis points making up the whole withdrawn amount
+pub const TOTAL_BPS: u32 = 10_000;
+
+/// Maximum number of beneficiaries in a payout split
+pub const MAX_PAYOUTS: u32 = 10;
+
+/// Share of a withdrawn swap paid to one beneficiary
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct Payout {
+    pub recipient: Address,
+    pub bps: u32, // Share in basis points; shares of a split sum to 10000
+}
+
+pub fn validate(payouts: &Vec<Payout>) {
+    if payouts.is_empty() {
+        return;
+    }
+    if payouts.len() > MAX_PAYOUTS {
+        panic!("too many payouts");
+    }
+
+    let mut total: u32 = 0;
+    for payout in payouts.iter() {
+        if payout.bps == 0 {
+            panic!("payout share must be positive");
+        }
+        total = total.checked_add(payout.bps).expect("payout shares overflow");
+    }
+    if total != TOTAL_BPS {
+        panic!("payout shares must sum to 10000 bps");
+    }
+}
+
+/// Transfer `amount` from the contract according to `payouts`, or entirely to
+/// `recipient` when there is no split. Rounding dust goes to the first beneficiary.
+pub fn pay(env: &Env, token: &Address, amount: i128, recipient: &Address, payouts: &Vec<Payout>) {
+    let token_client = token::Client::new(env, token);
+    let contract = env.current_contract_address();
+
+    if payouts.is_empty() {
+        token_client.transfer(&contract, recipient, &amount);
+        return;
+    }
+
+    let mut shares = Vec::new(env);
+    let mut distributed: i128 = 0;
+    for payout in payouts.iter() {
+        let share = amount * payout.bps as i128 / TOTAL_BPS as i128;
+        distributed += share;
+        shares.push_back(share);
+    }
+
+    let dust = amount - distributed;
+    for (i, payout) in payouts.iter().enumerate() {
+        let mut share = shares.get_unchecked(i as u32);
+        if i == 0 {
+            share += dust;
+        }
+        if share > 0 {
+            token_client.transfer(&contract, &payout.recipient, &share);
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use soroban_sdk::{token, Bytes, Env};
+
+use crate::denylist;
+use crate::events::{self, PremiumPaid};
+use crate::HTLCSwap;
+
+pub fn validate(premium: i128) {
+    if premium < 0 {
+        panic!("premium cannot be negative");
+    }
+}
+
+/// Release the premium of a swap that just closed: the recipient keeps it as
+/// compensation when the swap was refunded, otherwise it goes back to the sender.
+/// A denied recipient forfeits it to the sender.
+pub fn settle(env: &Env, swap_id: &Bytes, swap: &HTLCSwap) {
+    if swap.premium == 0 {
+        return;
+    }
+
+    let to = if swap.is_refunded && !denylist::is_denied(env, &swap.recipient) {
+        &swap.recipient
+    } else {
+        &swap.sender
+    };
+    let token_client = token::Client::new(env, &swap.token);
+    token_client.transfer(&env.current_contract_address(), to, &swap.premium);
+
+    events::premium_paid(
+        env,
+        swap_id,
+        &swap.hashlock,
+        PremiumPaid {
+            seq: events::next_seq(env),
+            to: to.clone(),
+            amount: swap.premium,
+        },
+    );
+}
@@ -0,0 +1,218 @@
+use soroban_sdk::{contracttype, token, Address, Bytes, Env};
+
+use crate::denylist;
+use crate::events::{self, ResolverBondUpdated, ResolverSlashed};
+use crate::{bump_persistent, get_locked_total, is_resolved, set_locked_total, DataKey, HTLCSwap};
+
+/// Default delay between `unbond` and `withdraw_bond`
+pub const DEFAULT_UNBOND_DELAY: u64 = 7 * 24 * 60 * 60;
+
+/// Bond of a registered resolver
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct Resolver {
+    pub bond: i128,      // Active stake; the resolver is bonded while it is at least `get_min_bond`
+    pub unbonding: i128, // Stake leaving the registry; still slashable until withdrawn
+    pub unbond_at: u64,  // Unix timestamp when `unbonding` can be withdrawn
+}
+
+pub fn bond_token(env: &Env) -> Address {
+    env.storage().instance().get(&DataKey::BondToken).expect("bond token not set")
+}
+
+pub fn set_bond_token(env: &Env, token: &Address) {
+    // Stakes are accounted in a single token, so it can't change once chosen
+    if env.storage().instance().has(&DataKey::BondToken) {
+        panic!("bond token already set");
+    }
+    env.storage().instance().set(&DataKey::BondToken, token);
+}
+
+pub fn min_bond(env: &Env) -> i128 {
+    env.storage().instance().get(&DataKey::MinBond).unwrap_or(0)
+}
+
+pub fn set_min_bond(env: &Env, amount: i128) {
+    if amount < 0 {
+        panic!("minimum bond cannot be negative");
+    }
+    env.storage().instance().set(&DataKey::MinBond, &amount);
+}
+
+pub fn unbond_delay(env: &Env) -> u64 {
+    env.storage().instance().get(&DataKey::UnbondDelay).unwrap_or(DEFAULT_UNBOND_DELAY)
+}
+
+pub fn get(env: &Env, resolver: &Address) -> Option<Resolver> {
+    env.storage().persistent().get(&DataKey::Resolver(resolver.clone()))
+}
+
+fn load(env: &Env, resolver: &Address) -> Resolver {
+    get(env, resolver).expect("resolver not registered")
+}
+
+fn store(env: &Env, resolver: &Address, state: &Resolver) {
+    let key = DataKey::Resolver(resolver.clone());
+    env.storage().persistent().set(&key, state);
+    bump_persistent(env, &key);
+
+    events::resolver_bond_updated(
+        env,
+        resolver,
+        ResolverBondUpdated {
+            seq: events::next_seq(env),
+            bond: state.bond,
+            unbonding: state.unbonding,
+            unbond_at: state.unbond_at,
+        },
+    );
+}
+
+/// Latest timelock of the swaps a resolver funded
+pub fn exposed_until(env: &Env, resolver: &Address) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ResolverExposure(resolver.clone()))
+        .unwrap_or(0)
+}
+
+/// Keep a resolver's bond slashable for a swap it funded expiring at `timelock`
+pub fn expose(env: &Env, resolver: &Address, timelock: u64) {
+    if timelock <= exposed_until(env, resolver) {
+        return;
+    }
+    let key = DataKey::ResolverExposure(resolver.clone());
+    env.storage().persistent().set(&key, &timelock);
+    bump_persistent(env, &key);
+}
+
+pub fn is_bonded(env: &Env, resolver: &Address) -> bool {
+    match get(env, resolver) {
+        Some(state) => state.bond > 0 && state.bond >= min_bond(env),
+        None => false,
+    }
+}
+
+pub fn ensure_bonded(env: &Env, resolver: &Address) {
+    if !is_bonded(env, resolver) {
+        panic!("resolver not bonded");
+    }
+}
+
+pub fn register(env: &Env, resolver: &Address) {
+    if get(env, resolver).is_some() {
+        panic!("resolver already registered");
+    }
+    store(env, resolver, &Resolver { bond: 0, unbonding: 0, unbond_at: 0 });
+}
+
+pub fn stake(env: &Env, resolver: &Address, amount: i128) {
+    if amount <= 0 {
+        panic!("amount must be positive");
+    }
+    let mut state = load(env, resolver);
+
+    let token = bond_token(env);
+    token::Client::new(env, &token).transfer(resolver, &env.current_contract_address(), &amount);
+    set_locked_total(env, &token, get_locked_total(env, &token) + amount);
+
+    state.bond += amount;
+    store(env, resolver, &state);
+}
+
+/// Move `amount` of the active bond into unbonding; restarts the unbond delay
+pub fn unbond(env: &Env, resolver: &Address, amount: i128) {
+    if amount <= 0 {
+        panic!("amount must be positive");
+    }
+    let mut state = load(env, resolver);
+    if amount > state.bond {
+        panic!("amount exceeds bond");
+    }
+
+    state.bond -= amount;
+    state.unbonding += amount;
+    state.unbond_at = env.ledger().timestamp() + unbond_delay(env);
+    store(env, resolver, &state);
+}
+
+/// Pay out the unbonding stake once the delay has passed and return the amount.
+/// The stake also stays until one unbond delay after the last swap the resolver
+/// funded expires, so that swap can still be slashed.
+pub fn withdraw_bond(env: &Env, resolver: &Address) -> i128 {
+    let mut state = load(env, resolver);
+    if state.unbonding == 0 {
+        panic!("nothing to withdraw");
+    }
+    let now = env.ledger().timestamp();
+    if now < state.unbond_at {
+        panic!("unbond delay not passed");
+    }
+    if now < exposed_until(env, resolver) + unbond_delay(env) {
+        panic!("bond still backs open swaps");
+    }
+
+    let amount = state.unbonding;
+    let token = bond_token(env);
+    token::Client::new(env, &token).transfer(&env.current_contract_address(), resolver, &amount);
+    set_locked_total(env, &token, get_locked_total(env, &token) - amount);
+
+    state.unbonding = 0;
+    state.unbond_at = 0;
+    store(env, resolver, &state);
+    amount
+}
+
+/// Take up to `amount` from the bond of the resolver that funded a refunded or
+/// expired swap, active stake first, and pay it to the swap's recipient. Returns
+/// the amount slashed.
+pub fn slash(env: &Env, swap_id: &Bytes, swap: &HTLCSwap, amount: i128) -> i128 {
+    if !swap.resolver_sender {
+        panic!("swap not funded by a resolver");
+    }
+    // The resolver decides when to refund, so an expired swap doesn't wait for it
+    let expired = !is_resolved(swap) && env.ledger().timestamp() >= swap.timelock;
+    if !swap.is_refunded && !expired {
+        panic!("swap not refunded or expired");
+    }
+    if amount <= 0 {
+        panic!("amount must be positive");
+    }
+    denylist::ensure_allowed(env, &swap.recipient);
+    let slashed_key = DataKey::Slashed(swap_id.clone());
+    if env.storage().persistent().has(&slashed_key) {
+        panic!("swap already slashed");
+    }
+
+    let mut state = load(env, &swap.sender);
+    let from_bond = amount.min(state.bond);
+    let from_unbonding = (amount - from_bond).min(state.unbonding);
+    let slashed = from_bond + from_unbonding;
+    if slashed == 0 {
+        panic!("nothing to slash");
+    }
+
+    let token = bond_token(env);
+    token::Client::new(env, &token).transfer(&env.current_contract_address(), &swap.recipient, &slashed);
+    set_locked_total(env, &token, get_locked_total(env, &token) - slashed);
+
+    env.storage().persistent().set(&slashed_key, &slashed);
+    bump_persistent(env, &slashed_key);
+
+    state.bond -= from_bond;
+    state.unbonding -= from_unbonding;
+    store(env, &swap.sender, &state);
+
+    events::resolver_slashed(
+        env,
+        swap_id,
+        &swap.hashlock,
+        ResolverSlashed {
+            seq: events::next_seq(env),
+            resolver: swap.sender.clone(),
+            to: swap.recipient.clone(),
+            amount: slashed,
+        },
+    );
+    slashed
+}
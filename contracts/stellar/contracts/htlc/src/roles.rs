@@ -0,0 +1,134 @@
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::events::{self, OwnershipProposed, OwnershipTransferred, RoleChanged};
+use crate::DataKey;
+
+/// Privileged roles. There is a single owner, transferred in two steps; the other
+/// roles are granted and revoked by the owner and may have several holders.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum Role {
+    Owner,        // Manages roles and general configuration
+    Pauser,       // Pauses and unpauses swap creation
+    FeeManager,   // Schedules fee cap changes
+    TokenManager, // Chooses the bond token, skims surplus tokens and schedules delistings
+    Upgrader,     // Schedules code upgrades
+}
+
+/// Roles other than `Owner`, all held by the owner set in the constructor
+const GRANTED_ROLES: [Role; 4] = [Role::Pauser, Role::FeeManager, Role::TokenManager, Role::Upgrader];
+
+pub fn owner(env: &Env) -> Address {
+    env.storage().instance().get(&DataKey::Admin).expect("admin not set")
+}
+
+pub fn require_owner(env: &Env) {
+    owner(env).require_auth();
+}
+
+pub fn has_role(env: &Env, role: Role, account: &Address) -> bool {
+    match role {
+        Role::Owner => owner(env) == *account,
+        _ => env.storage().instance().has(&DataKey::Role(role, account.clone())),
+    }
+}
+
+pub fn require_role(env: &Env, caller: &Address, role: Role) {
+    caller.require_auth();
+    if !has_role(env, role, caller) {
+        panic!("caller lacks role");
+    }
+}
+
+/// Grant a role; does nothing if `account` already holds it
+pub fn grant(env: &Env, role: Role, account: &Address) {
+    if role == Role::Owner {
+        panic!("ownership is transferred with propose_owner");
+    }
+    let key = DataKey::Role(role, account.clone());
+    if env.storage().instance().has(&key) {
+        return;
+    }
+    env.storage().instance().set(&key, &true);
+
+    events::role_granted(
+        env,
+        account,
+        RoleChanged {
+            seq: events::next_seq(env),
+            role,
+        },
+    );
+}
+
+/// Revoke a role; does nothing if `account` doesn't hold it
+pub fn revoke(env: &Env, role: Role, account: &Address) {
+    if role == Role::Owner {
+        panic!("ownership is transferred with propose_owner");
+    }
+    let key = DataKey::Role(role, account.clone());
+    if !env.storage().instance().has(&key) {
+        return;
+    }
+    env.storage().instance().remove(&key);
+
+    events::role_revoked(
+        env,
+        account,
+        RoleChanged {
+            seq: events::next_seq(env),
+            role,
+        },
+    );
+}
+
+pub fn pending_owner(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::PendingOwner)
+}
+
+pub fn propose_owner(env: &Env, new_owner: &Address) {
+    env.storage().instance().set(&DataKey::PendingOwner, new_owner);
+
+    events::ownership_proposed(
+        env,
+        new_owner,
+        OwnershipProposed {
+            seq: events::next_seq(env),
+            owner: owner(env),
+        },
+    );
+}
+
+/// Make the pending owner the owner and move the previous owner's other roles to it
+pub fn accept_ownership(env: &Env) {
+    let new_owner = pending_owner(env).expect("no pending owner");
+    new_owner.require_auth();
+
+    let previous_owner = owner(env);
+    env.storage().instance().set(&DataKey::Admin, &new_owner);
+    env.storage().instance().remove(&DataKey::PendingOwner);
+    for role in GRANTED_ROLES {
+        if has_role(env, role, &previous_owner) {
+            revoke(env, role, &previous_owner);
+            grant(env, role, &new_owner);
+        }
+    }
+
+    events::ownership_transferred(
+        env,
+        &new_owner,
+        OwnershipTransferred {
+            seq: events::next_seq(env),
+            previous_owner,
+        },
+    );
+}
+
+/// Make `owner` the owner and holder of every other role. Runs in the constructor,
+/// so it publishes no events.
+pub fn init(env: &Env, owner: &Address) {
+    env.storage().instance().set(&DataKey::Admin, owner);
+    for role in GRANTED_ROLES {
+        env.storage().instance().set(&DataKey::Role(role, owner.clone()), &true);
+    }
+}
@@ -0,0 +1,56 @@
+use soroban_sdk::{contractclient, token, Address, Env, Vec};
+
+use crate::DataKey;
+
+/// Soroswap-style router interface used by `withdraw_and_swap`
+#[contractclient(name = "SwapRouterClient")]
+pub trait SwapRouter {
+    /// Sell exactly `amount_in` of `path[0]` held by `to` along `path`, sending the
+    /// output to `to`. Returns the amounts at each hop; the last is the output.
+    fn swap_exact_tokens_for_tokens(
+        env: Env,
+        amount_in: i128,
+        amount_out_min: i128,
+        path: Vec<Address>,
+        to: Address,
+        deadline: u64,
+    ) -> Vec<i128>;
+}
+
+pub fn validate(env: &Env, router: &Address, token: &Address, path: &Vec<Address>, min_out: i128) {
+    let allowed: Option<Address> = env.storage().instance().get(&DataKey::Router);
+    if allowed.as_ref() != Some(router) {
+        panic!("router not allowed");
+    }
+    if path.len() < 2 {
+        panic!("path must have at least two tokens");
+    }
+    if path.get_unchecked(0) != *token {
+        panic!("path must start with the swap token");
+    }
+    if min_out <= 0 {
+        panic!("min_out must be positive");
+    }
+}
+
+/// Sell `amount_in` held by `to` through the router and return the amount of the
+/// output token `to` actually gained
+pub fn swap(env: &Env, router: &Address, path: &Vec<Address>, to: &Address, amount_in: i128, min_out: i128) -> i128 {
+    let token_out = token::Client::new(env, &path.last_unchecked());
+    let balance_before = token_out.balance(to);
+
+    SwapRouterClient::new(env, router).swap_exact_tokens_for_tokens(
+        &amount_in,
+        &min_out,
+        path,
+        to,
+        &env.ledger().timestamp(),
+    );
+
+    // Don't trust the router's reported amounts
+    let amount_out = token_out.balance(to) - balance_before;
+    if amount_out < min_out {
+        panic!("insufficient output amount");
+    }
+    amount_out
+}
@@ -0,0 +1,72 @@
+use soroban_sdk::{contracttype, Address, Env, Map};
+
+use crate::{bump_persistent, DataKey, HTLCSwap};
+
+/// Outcome counters of one address across all swaps
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct ParticipantStats {
+    pub created: u32,   // Swaps funded as sender
+    pub withdrawn: u32, // Swaps withdrawn, as sender or recipient
+    pub refunded: u32,  // Swaps refunded, as sender or recipient
+    pub cancelled: u32, // Swaps cancelled, as sender or recipient
+    pub settled: u32,   // Escrow swaps settled, as sender or recipient
+    pub volume: Map<Address, i128>, // Amount locked as sender, including top-ups, by token
+}
+
+pub fn get(env: &Env, participant: &Address) -> ParticipantStats {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ParticipantStats(participant.clone()))
+        .unwrap_or(ParticipantStats {
+            created: 0,
+            withdrawn: 0,
+            refunded: 0,
+            cancelled: 0,
+            settled: 0,
+            volume: Map::new(env),
+        })
+}
+
+fn update<F>(env: &Env, participant: &Address, f: F)
+where
+    F: FnOnce(&mut ParticipantStats),
+{
+    let mut stats = get(env, participant);
+    f(&mut stats);
+
+    let key = DataKey::ParticipantStats(participant.clone());
+    env.storage().persistent().set(&key, &stats);
+    bump_persistent(env, &key);
+}
+
+/// Add locked funds to the sender's volume; `new_swap` also counts it as created
+pub fn record_locked(env: &Env, sender: &Address, token: &Address, amount: i128, new_swap: bool) {
+    update(env, sender, |stats| {
+        if new_swap {
+            stats.created += 1;
+        }
+        let volume = stats.volume.get(token.clone()).unwrap_or(0);
+        stats.volume.set(token.clone(), volume + amount);
+    });
+}
+
+/// Count the outcome of a swap that just closed for both of its parties
+pub fn record_closed(env: &Env, swap: &HTLCSwap) {
+    for participant in [&swap.sender, &swap.recipient] {
+        update(env, participant, |stats| {
+            if swap.is_withdrawn {
+                stats.withdrawn += 1;
+            } else if swap.is_refunded {
+                stats.refunded += 1;
+            } else if swap.is_cancelled {
+                stats.cancelled += 1;
+            } else {
+                stats.settled += 1;
+            }
+        });
+        if swap.sender == swap.recipient {
+            break;
+        }
+    }
+}
@@ -4,8 +4,8 @@ use std::println;
 
 use super::*;
 use soroban_sdk::{
-    testutils::{Address as _, Ledger, Events},
-    token, Address, Env, Bytes,
+    testutils::{storage::Instance as _, Address as _, Ledger, Events, MockAuth, MockAuthInvoke},
+    token, vec, Address, Env, Bytes, BytesN, FromVal, IntoVal, Symbol, Val, U256,
 };
 use token::Client as TokenClient;
 use token::StellarAssetClient as TokenAdminClient;
@@ -18,12 +18,13 @@ fn create_token_contract<'a>(e: &Env, admin: &Address) -> (TokenClient<'a>, Toke
     )
 }
 
-fn create_htlc_contract<'a>(e: &Env) -> HTLCContractClient<'a> {
-    HTLCContractClient::new(e, &e.register(HTLCContract, ()))
+fn create_htlc_contract<'a>(e: &Env, admin: &Address) -> HTLCContractClient<'a> {
+    HTLCContractClient::new(e, &e.register(HTLCContract, (admin,)))
 }
 
 struct HTLCTest<'a> {
     env: Env,
+    admin: Address,
     sender: Address,
     recipient: Address,
     token: TokenClient<'a>,
@@ -49,7 +50,8 @@ impl<'a> HTLCTest<'a> {
         let (token, token_admin_client) = create_token_contract(&env, &token_admin);
         token_admin_client.mint(&sender, &1000);
 
-        let contract = create_htlc_contract(&env);
+        let admin = Address::generate(&env);
+        let contract = create_htlc_contract(&env, &admin);
 
         // Create a test preimage and hashlock
         let preimage = Bytes::from_slice(&env, b"secret_preimage_123");
@@ -61,6 +63,7 @@ impl<'a> HTLCTest<'a> {
 
         HTLCTest {
             env,
+            admin,
             sender,
             recipient,
             token,
@@ -71,6 +74,48 @@ impl<'a> HTLCTest<'a> {
         }
     }
 
+    /// Topics and payload of the last event published by the HTLC contract
+    fn last_htlc_event(&self) -> (Vec<Val>, Val) {
+        let events = self.env.events().all();
+        let (_, topics, data) = events
+            .iter()
+            .filter(|(contract, _, _)| *contract == self.contract.address)
+            .last()
+            .expect("no HTLC event emitted");
+        (topics, data)
+    }
+
+    /// Schedule an operation as the admin and execute it once ready, leaving the
+    /// ledger time where it was
+    fn govern(&self, operation: &Operation) {
+        let now = self.env.ledger().timestamp();
+        let id = self.contract.schedule_operation(&self.admin, operation);
+        self.env.ledger().with_mut(|li| {
+            li.timestamp = now + self.contract.get_min_delay();
+        });
+        self.contract.execute_operation(&id);
+        self.env.ledger().with_mut(|li| {
+            li.timestamp = now;
+        });
+    }
+
+    fn set_max_integrator_fee_bps(&self, bps: u32) {
+        self.govern(&Operation::SetMaxIntegratorFeeBps(bps));
+    }
+
+    fn create_swap_with_options(&self, amount: i128, options: &SwapOptions) {
+        self.contract.create_swap_with_options(
+            &self.swap_id,
+            &self.sender,
+            &self.recipient,
+            &self.token.address,
+            &amount,
+            &self.hashlock,
+            &12350,
+            options,
+        );
+    }
+
     fn echo_events(&self, operation: &str) {
         println!("\n=== Events after {} ===", operation);
         let events = self.env.events().all();
@@ -547,3 +592,3599 @@ fn test_multiple_swaps() {
     assert!(!swap2_after.is_withdrawn);
     assert!(!swap2_after.is_refunded);
 }
+
+#[test]
+fn test_locked_total_tracks_open_swaps() {
+    let test = HTLCTest::setup();
+    let swap_id_2 = Bytes::from_slice(&test.env, b"test_swap_002");
+
+    assert_eq!(test.contract.get_locked_total(&test.token.address), 0);
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &400,
+        &test.hashlock,
+        &12350,
+    );
+    test.contract.create_swap(
+        &swap_id_2,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &300,
+        &test.hashlock,
+        &12350,
+    );
+    assert_eq!(test.contract.get_locked_total(&test.token.address), 700);
+
+    // Withdraw releases the first swap's liability
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+    assert_eq!(test.contract.get_locked_total(&test.token.address), 300);
+
+    // Refund releases the second
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12351;
+    });
+    test.contract.refund(&swap_id_2, &test.sender);
+    assert_eq!(test.contract.get_locked_total(&test.token.address), 0);
+}
+
+#[test]
+fn test_swap_writes_extend_instance_ttl() {
+    let test = HTLCTest::setup();
+    let instance_ttl = || test.env.as_contract(&test.contract.address, || test.env.storage().instance().get_ttl());
+    assert!(instance_ttl() < PERSISTENT_TTL_EXTEND_TO);
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &400,
+        &test.hashlock,
+        &12350,
+    );
+    assert_eq!(instance_ttl(), PERSISTENT_TTL_EXTEND_TO);
+}
+
+#[test]
+fn test_new_tokens_do_not_grow_instance() {
+    let test = HTLCTest::setup();
+    let instance_len = || {
+        test.env.as_contract(&test.contract.address, || test.env.storage().instance().all().len())
+    };
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &400,
+        &test.hashlock,
+        &12350,
+    );
+    let len = instance_len();
+
+    for id in [b"token_swap_1", b"token_swap_2", b"token_swap_3"] {
+        let (token, token_admin) = create_token_contract(&test.env, &test.admin);
+        token_admin.mint(&test.sender, &100);
+        test.contract.create_swap(
+            &Bytes::from_slice(&test.env, id),
+            &test.sender,
+            &test.recipient,
+            &token.address,
+            &100,
+            &test.hashlock,
+            &12350,
+        );
+        assert_eq!(test.contract.get_locked_total(&token.address), 100);
+    }
+    assert_eq!(instance_len(), len);
+}
+
+#[test]
+fn test_reconcile() {
+    let test = HTLCTest::setup();
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+
+    let report = test.contract.reconcile(&test.token.address);
+    assert_eq!(report.token, test.token.address);
+    assert_eq!(report.balance, 800);
+    assert_eq!(report.locked, 800);
+    assert_eq!(report.surplus, 0);
+
+    // Accidental direct transfer to the contract shows up as surplus
+    test.token.transfer(&test.sender, &test.contract.address, &50);
+    let report = test.contract.reconcile(&test.token.address);
+    assert_eq!(report.balance, 850);
+    assert_eq!(report.locked, 800);
+    assert_eq!(report.surplus, 50);
+}
+
+#[test]
+fn test_skim_recovers_only_surplus() {
+    let test = HTLCTest::setup();
+    let treasury = Address::generate(&test.env);
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+    test.token.transfer(&test.sender, &test.contract.address, &50);
+
+    let skimmed = test.contract.skim(&test.admin, &test.token.address, &treasury);
+
+    // Verify admin authorization was required
+    assert_eq!(test.env.auths()[0].0, test.admin);
+
+    assert_eq!(skimmed, 50);
+    assert_eq!(test.token.balance(&treasury), 50);
+    assert_eq!(test.token.balance(&test.contract.address), 800);
+    assert_eq!(test.contract.reconcile(&test.token.address).surplus, 0);
+
+    // Open swap is still fully backed
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+    assert_eq!(test.token.balance(&test.recipient), 800);
+}
+
+#[test]
+#[should_panic(expected = "no surplus to skim")]
+fn test_skim_without_surplus() {
+    let test = HTLCTest::setup();
+    let treasury = Address::generate(&test.env);
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+
+    test.contract.skim(&test.admin, &test.token.address, &treasury);
+}
+
+#[test]
+fn test_swaps_by_sender_and_recipient() {
+    let test = HTLCTest::setup();
+    let other_recipient = Address::generate(&test.env);
+    let swap_id_2 = Bytes::from_slice(&test.env, b"test_swap_002");
+    let swap_id_3 = Bytes::from_slice(&test.env, b"test_swap_003");
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &100,
+        &test.hashlock,
+        &12350,
+    );
+    test.contract.create_swap(
+        &swap_id_2,
+        &test.sender,
+        &other_recipient,
+        &test.token.address,
+        &100,
+        &test.hashlock,
+        &12350,
+    );
+    test.contract.create_swap(
+        &swap_id_3,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &100,
+        &test.hashlock,
+        &12350,
+    );
+
+    let by_sender = test.contract.get_swaps_by_sender(&test.sender, &0, &10);
+    assert_eq!(by_sender.len(), 3);
+    assert_eq!(by_sender.get(0).unwrap(), test.swap_id);
+    assert_eq!(by_sender.get(1).unwrap(), swap_id_2);
+    assert_eq!(by_sender.get(2).unwrap(), swap_id_3);
+
+    let by_recipient = test.contract.get_swaps_by_recipient(&test.recipient, &0, &10);
+    assert_eq!(by_recipient.len(), 2);
+    assert_eq!(by_recipient.get(0).unwrap(), test.swap_id);
+    assert_eq!(by_recipient.get(1).unwrap(), swap_id_3);
+
+    let by_other = test.contract.get_swaps_by_recipient(&other_recipient, &0, &10);
+    assert_eq!(by_other.len(), 1);
+    assert_eq!(by_other.get(0).unwrap(), swap_id_2);
+
+    // Unknown address has an empty index
+    let stranger = Address::generate(&test.env);
+    assert_eq!(test.contract.get_swaps_by_sender(&stranger, &0, &10).len(), 0);
+}
+
+#[test]
+fn test_swaps_by_sender_pagination() {
+    let test = HTLCTest::setup();
+
+    for i in 0..5u8 {
+        let swap_id = Bytes::from_slice(&test.env, &[b's', i]);
+        test.contract.create_swap(
+            &swap_id,
+            &test.sender,
+            &test.recipient,
+            &test.token.address,
+            &10,
+            &test.hashlock,
+            &12350,
+        );
+    }
+
+    let page1 = test.contract.get_swaps_by_sender(&test.sender, &0, &2);
+    let page2 = test.contract.get_swaps_by_sender(&test.sender, &2, &2);
+    let page3 = test.contract.get_swaps_by_sender(&test.sender, &4, &2);
+    let past_end = test.contract.get_swaps_by_sender(&test.sender, &5, &2);
+
+    assert_eq!(page1.len(), 2);
+    assert_eq!(page2.len(), 2);
+    assert_eq!(page3.len(), 1);
+    assert_eq!(past_end.len(), 0);
+    assert_eq!(page1.get(0).unwrap(), Bytes::from_slice(&test.env, &[b's', 0]));
+    assert_eq!(page2.get(0).unwrap(), Bytes::from_slice(&test.env, &[b's', 2]));
+    assert_eq!(page3.get(0).unwrap(), Bytes::from_slice(&test.env, &[b's', 4]));
+}
+
+#[test]
+fn test_indexes_survive_withdraw_and_refund() {
+    let test = HTLCTest::setup();
+    let swap_id_2 = Bytes::from_slice(&test.env, b"test_swap_002");
+    let preimage_2 = Bytes::from_slice(&test.env, b"another_secret");
+    let hashlock_2 = Bytes::from_slice(&test.env, &test.env.crypto().sha256(&preimage_2).to_array());
+
+    assert_eq!(test.contract.get_swap_id_by_hashlock(&test.hashlock), None);
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &400,
+        &test.hashlock,
+        &12350,
+    );
+    test.contract.create_swap(
+        &swap_id_2,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &300,
+        &hashlock_2,
+        &12350,
+    );
+
+    assert_eq!(test.contract.get_swap_id_by_hashlock(&test.hashlock), Some(test.swap_id.clone()));
+    assert_eq!(test.contract.get_swap_id_by_hashlock(&hashlock_2), Some(swap_id_2.clone()));
+
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12351;
+    });
+    test.contract.refund(&swap_id_2, &test.sender);
+
+    assert_eq!(test.contract.get_swaps_by_sender(&test.sender, &0, &10).len(), 2);
+    assert_eq!(test.contract.get_swaps_by_recipient(&test.recipient, &0, &10).len(), 2);
+    assert_eq!(test.contract.get_swap_id_by_hashlock(&test.hashlock), Some(test.swap_id.clone()));
+    assert_eq!(test.contract.get_swap_id_by_hashlock(&hashlock_2), Some(swap_id_2));
+}
+
+#[test]
+fn test_get_swaps_and_statuses() {
+    let test = HTLCTest::setup();
+    let swap_id_2 = Bytes::from_slice(&test.env, b"test_swap_002");
+    let swap_id_3 = Bytes::from_slice(&test.env, b"test_swap_003");
+    let missing = Bytes::from_slice(&test.env, b"missing");
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &100,
+        &test.hashlock,
+        &12350,
+    );
+    test.contract.create_swap(
+        &swap_id_2,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &200,
+        &test.hashlock,
+        &12350,
+    );
+    test.contract.create_swap(
+        &swap_id_3,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &300,
+        &test.hashlock,
+        &12360,
+    );
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+
+    let ids = vec![&test.env, test.swap_id.clone(), missing.clone(), swap_id_2.clone(), swap_id_3.clone()];
+
+    let swaps = test.contract.get_swaps(&ids);
+    assert_eq!(swaps.len(), 4);
+    assert_eq!(swaps.get(0).unwrap().unwrap().amount, 100);
+    assert!(swaps.get(1).unwrap().is_none());
+    assert_eq!(swaps.get(2).unwrap().unwrap().amount, 200);
+    assert_eq!(swaps.get(3).unwrap().unwrap().amount, 300);
+
+    let statuses = test.contract.get_statuses(&ids);
+    assert_eq!(statuses.get(0).unwrap(), Some(SwapStatus::Withdrawn));
+    assert_eq!(statuses.get(1).unwrap(), None);
+    assert_eq!(statuses.get(2).unwrap(), Some(SwapStatus::Open));
+    assert_eq!(statuses.get(3).unwrap(), Some(SwapStatus::Open));
+
+    // Second swap expires, then gets refunded
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12351;
+    });
+    let statuses = test.contract.get_statuses(&ids);
+    assert_eq!(statuses.get(2).unwrap(), Some(SwapStatus::Expired));
+    assert_eq!(statuses.get(3).unwrap(), Some(SwapStatus::Open));
+
+    test.contract.refund(&swap_id_2, &test.sender);
+    let statuses = test.contract.get_statuses(&ids);
+    assert_eq!(statuses.get(2).unwrap(), Some(SwapStatus::Refunded));
+}
+
+#[test]
+#[should_panic(expected = "too many swap ids")]
+fn test_get_swaps_too_many_ids() {
+    let test = HTLCTest::setup();
+
+    let mut ids = Vec::new(&test.env);
+    for i in 0..31u8 {
+        ids.push_back(Bytes::from_slice(&test.env, &[i]));
+    }
+
+    test.contract.get_swaps(&ids);
+}
+
+#[test]
+fn test_get_open_swaps() {
+    let test = HTLCTest::setup();
+    let swap_id_2 = Bytes::from_slice(&test.env, b"test_swap_002");
+    let swap_id_3 = Bytes::from_slice(&test.env, b"test_swap_003");
+
+    for swap_id in [&test.swap_id, &swap_id_2, &swap_id_3] {
+        test.contract.create_swap(
+            swap_id,
+            &test.sender,
+            &test.recipient,
+            &test.token.address,
+            &100,
+            &test.hashlock,
+            &12350,
+        );
+    }
+    assert_eq!(
+        test.contract.get_open_swaps(&0, &10),
+        vec![&test.env, test.swap_id.clone(), swap_id_2.clone(), swap_id_3.clone()]
+    );
+
+    // Resolving the first swap moves the last one into its slot
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+    assert_eq!(
+        test.contract.get_open_swaps(&0, &10),
+        vec![&test.env, swap_id_3.clone(), swap_id_2.clone()]
+    );
+
+    // Pagination
+    assert_eq!(test.contract.get_open_swaps(&1, &1), vec![&test.env, swap_id_2.clone()]);
+
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12351;
+    });
+    test.contract.refund(&swap_id_2, &test.sender);
+    test.contract.refund(&swap_id_3, &test.sender);
+    assert_eq!(test.contract.get_open_swaps(&0, &10).len(), 0);
+}
+
+fn assert_swap_topics(test: &HTLCTest, topics: &Vec<Val>, name: Symbol, swap_id: &Bytes) {
+    assert_eq!(topics.len(), 4);
+    assert_eq!(Symbol::from_val(&test.env, &topics.get(0).unwrap()), name);
+    assert_eq!(u32::from_val(&test.env, &topics.get(1).unwrap()), events::EVENT_VERSION);
+    assert_eq!(Bytes::from_val(&test.env, &topics.get(2).unwrap()), *swap_id);
+    assert_eq!(Bytes::from_val(&test.env, &topics.get(3).unwrap()), test.hashlock);
+}
+
+#[test]
+fn test_swap_created_event() {
+    let test = HTLCTest::setup();
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+
+    let (topics, data) = test.last_htlc_event();
+    assert_swap_topics(&test, &topics, events::SWAP_CREATED, &test.swap_id);
+    assert_eq!(
+        SwapCreated::from_val(&test.env, &data),
+        SwapCreated {
+            seq: 1,
+            sender: test.sender.clone(),
+            recipient: test.recipient.clone(),
+            token: test.token.address.clone(),
+            amount: 800,
+            timelock: 12350,
+            options: SwapOptions::none(&test.env),
+        }
+    );
+}
+
+#[test]
+fn test_swap_created_event_carries_options() {
+    let test = HTLCTest::setup();
+    let arbiter = Address::generate(&test.env);
+    let mut options = premium_options(&test.env, 50);
+    options.arbiter = Some(arbiter);
+
+    test.create_swap_with_options(800, &options);
+
+    let (_, data) = test.last_htlc_event();
+    assert_eq!(SwapCreated::from_val(&test.env, &data).options, options);
+}
+
+#[test]
+fn test_swap_withdrawn_event() {
+    let test = HTLCTest::setup();
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+
+    let (topics, data) = test.last_htlc_event();
+    assert_swap_topics(&test, &topics, events::SWAP_WITHDRAWN, &test.swap_id);
+    assert_eq!(
+        SwapWithdrawn::from_val(&test.env, &data),
+        SwapWithdrawn {
+            seq: 2,
+            sender: test.sender.clone(),
+            recipient: test.recipient.clone(),
+            token: test.token.address.clone(),
+            amount: 800,
+            preimage: test.preimage.clone(),
+        }
+    );
+}
+
+#[test]
+fn test_swap_refunded_event() {
+    let test = HTLCTest::setup();
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12351;
+    });
+    test.contract.refund(&test.swap_id, &test.sender);
+
+    let (topics, data) = test.last_htlc_event();
+    assert_swap_topics(&test, &topics, events::SWAP_REFUNDED, &test.swap_id);
+    assert_eq!(
+        SwapRefunded::from_val(&test.env, &data),
+        SwapRefunded {
+            seq: 2,
+            sender: test.sender.clone(),
+            recipient: test.recipient.clone(),
+            token: test.token.address.clone(),
+            amount: 800,
+        }
+    );
+}
+
+#[test]
+fn test_tokens_skimmed_event() {
+    let test = HTLCTest::setup();
+    let treasury = Address::generate(&test.env);
+
+    test.token.transfer(&test.sender, &test.contract.address, &50);
+    test.contract.skim(&test.admin, &test.token.address, &treasury);
+
+    let (topics, data) = test.last_htlc_event();
+    assert_eq!(topics.len(), 3);
+    assert_eq!(Symbol::from_val(&test.env, &topics.get(0).unwrap()), events::SKIMMED);
+    assert_eq!(u32::from_val(&test.env, &topics.get(1).unwrap()), events::EVENT_VERSION);
+    assert_eq!(Address::from_val(&test.env, &topics.get(2).unwrap()), test.token.address);
+    assert_eq!(
+        TokensSkimmed::from_val(&test.env, &data),
+        TokensSkimmed { seq: 1, to: treasury, amount: 50 }
+    );
+}
+
+#[test]
+fn test_event_seq_is_monotonic() {
+    let test = HTLCTest::setup();
+    let swap_id_2 = Bytes::from_slice(&test.env, b"test_swap_002");
+
+    assert_eq!(test.contract.get_event_seq(), 0);
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &400,
+        &test.hashlock,
+        &12350,
+    );
+    let (_, data) = test.last_htlc_event();
+    assert_eq!(SwapCreated::from_val(&test.env, &data).seq, 1);
+
+    test.contract.create_swap(
+        &swap_id_2,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &300,
+        &test.hashlock,
+        &12350,
+    );
+    let (_, data) = test.last_htlc_event();
+    assert_eq!(SwapCreated::from_val(&test.env, &data).seq, 2);
+
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+    let (_, data) = test.last_htlc_event();
+    assert_eq!(SwapWithdrawn::from_val(&test.env, &data).seq, 3);
+    assert_eq!(test.contract.get_event_seq(), 3);
+
+    // Failed calls don't consume a sequence number
+    let wrong_preimage = Bytes::from_slice(&test.env, b"wrong_preimage");
+    assert!(test.contract.try_withdraw(&swap_id_2, &test.recipient, &wrong_preimage).is_err());
+    assert_eq!(test.contract.get_event_seq(), 3);
+}
+
+#[test]
+fn test_cancel_returns_funds_before_timelock() {
+    let test = HTLCTest::setup();
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+
+    test.contract.cancel(&test.swap_id);
+
+    // Verify recipient authorization was required
+    assert_eq!(test.env.auths().len(), 1);
+    assert_eq!(test.env.auths()[0].0, test.recipient);
+
+    let (topics, data) = test.last_htlc_event();
+    assert_swap_topics(&test, &topics, events::SWAP_CANCELLED, &test.swap_id);
+    assert_eq!(
+        SwapCancelled::from_val(&test.env, &data),
+        SwapCancelled {
+            seq: 2,
+            sender: test.sender.clone(),
+            recipient: test.recipient.clone(),
+            token: test.token.address.clone(),
+            amount: 800,
+        }
+    );
+
+    assert_eq!(test.token.balance(&test.sender), 1000);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+    assert_eq!(test.contract.get_locked_total(&test.token.address), 0);
+    assert_eq!(test.contract.get_open_swaps(&0, &10).len(), 0);
+
+    let swap = test.contract.get_swap(&test.swap_id).unwrap();
+    assert!(swap.is_cancelled);
+    assert!(!swap.is_withdrawn);
+    assert!(!swap.is_refunded);
+    assert_eq!(
+        test.contract.get_statuses(&vec![&test.env, test.swap_id.clone()]),
+        vec![&test.env, Some(SwapStatus::Cancelled)]
+    );
+
+}
+
+#[test]
+fn test_cancel_requires_recipient_auth() {
+    let test = HTLCTest::setup();
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+
+    test.env.set_auths(&[]);
+    assert!(test.contract.try_cancel(&test.swap_id).is_err());
+}
+
+#[test]
+#[should_panic(expected = "timelock expired")]
+fn test_cancel_after_expiry() {
+    let test = HTLCTest::setup();
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12350;
+    });
+
+    test.contract.cancel(&test.swap_id);
+}
+
+#[test]
+#[should_panic(expected = "swap already cancelled")]
+fn test_withdraw_after_cancel() {
+    let test = HTLCTest::setup();
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+    test.contract.cancel(&test.swap_id);
+
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+}
+
+#[test]
+#[should_panic(expected = "swap already withdrawn")]
+fn test_cancel_after_withdraw() {
+    let test = HTLCTest::setup();
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+
+    test.contract.cancel(&test.swap_id);
+}
+
+#[test]
+fn test_extend_timelock() {
+    let test = HTLCTest::setup();
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+
+    test.contract.extend_timelock(&test.swap_id, &12400);
+
+    // Verify both parties' authorization was required
+    assert_eq!(test.env.auths().len(), 2);
+    assert_eq!(test.env.auths()[0].0, test.sender);
+    assert_eq!(test.env.auths()[1].0, test.recipient);
+
+    let (topics, data) = test.last_htlc_event();
+    assert_swap_topics(&test, &topics, events::TIMELOCK_EXTENDED, &test.swap_id);
+    assert_eq!(
+        TimelockExtended::from_val(&test.env, &data),
+        TimelockExtended { seq: 2, old_timelock: 12350, new_timelock: 12400 }
+    );
+
+    assert_eq!(test.contract.get_swap(&test.swap_id).unwrap().timelock, 12400);
+
+    // Refund is now blocked past the old timelock, withdraw still possible
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12360;
+    });
+    assert!(test.contract.try_refund(&test.swap_id, &test.sender).is_err());
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+    assert_eq!(test.token.balance(&test.recipient), 800);
+}
+
+#[test]
+#[should_panic(expected = "new timelock exceeds maximum horizon")]
+fn test_extend_timelock_beyond_horizon() {
+    let test = HTLCTest::setup();
+
+    test.govern(&Operation::SetMaxTimelockHorizon(100));
+    assert_eq!(test.contract.get_max_timelock_horizon(), 100);
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+
+    test.contract.extend_timelock(&test.swap_id, &(12350 + 101));
+}
+
+#[test]
+fn test_repeated_extensions_stay_within_horizon() {
+    let test = HTLCTest::setup();
+    test.govern(&Operation::SetMaxTimelockHorizon(100));
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+
+    // Each extension stays close to the ledger time, but together they can't
+    // push the timelock past the initial one plus the horizon
+    for new_timelock in [12400, 12440, 12450] {
+        test.env.ledger().with_mut(|li| {
+            li.timestamp = new_timelock - 60;
+        });
+        test.contract.extend_timelock(&test.swap_id, &new_timelock);
+    }
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12440;
+    });
+    assert!(test.contract.try_extend_timelock(&test.swap_id, &12460).is_err());
+    assert_eq!(test.contract.get_swap(&test.swap_id).unwrap().timelock, 12450);
+}
+
+#[test]
+fn test_extend_timelock_requires_sender() {
+    let test = HTLCTest::setup();
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+
+    // The recipient alone can't delay the sender's refund
+    test.env.mock_auths(&[MockAuth {
+        address: &test.recipient,
+        invoke: &MockAuthInvoke {
+            contract: &test.contract.address,
+            fn_name: "extend_timelock",
+            args: (test.swap_id.clone(), 12400u64).into_val(&test.env),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(test.contract.try_extend_timelock(&test.swap_id, &12400).is_err());
+}
+
+#[test]
+#[should_panic(expected = "new timelock must be later than current timelock")]
+fn test_extend_timelock_shorter() {
+    let test = HTLCTest::setup();
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+
+    test.contract.extend_timelock(&test.swap_id, &12349);
+}
+
+#[test]
+#[should_panic(expected = "timelock expired")]
+fn test_extend_timelock_after_expiry() {
+    let test = HTLCTest::setup();
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12350;
+    });
+
+    test.contract.extend_timelock(&test.swap_id, &12400);
+}
+
+#[test]
+fn test_max_timelock_horizon_change_is_queued() {
+    let test = HTLCTest::setup();
+
+    assert_eq!(test.contract.get_max_timelock_horizon(), DEFAULT_MAX_TIMELOCK_HORIZON);
+
+    test.contract.schedule_operation(&test.admin, &Operation::SetMaxTimelockHorizon(3600));
+    assert_eq!(test.contract.get_max_timelock_horizon(), DEFAULT_MAX_TIMELOCK_HORIZON);
+
+    test.govern(&Operation::SetMaxTimelockHorizon(3600));
+    assert_eq!(test.contract.get_max_timelock_horizon(), 3600);
+}
+
+#[test]
+#[should_panic(expected = "horizon must be positive")]
+fn test_schedule_zero_timelock_horizon() {
+    let test = HTLCTest::setup();
+
+    test.contract.schedule_operation(&test.admin, &Operation::SetMaxTimelockHorizon(0));
+}
+
+#[test]
+fn test_top_up() {
+    let test = HTLCTest::setup();
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &600,
+        &test.hashlock,
+        &12350,
+    );
+
+    test.contract.top_up(&test.swap_id, &150);
+
+    // Verify sender authorization was required
+    assert_eq!(test.env.auths().len(), 1);
+    assert_eq!(test.env.auths()[0].0, test.sender);
+
+    let (topics, data) = test.last_htlc_event();
+    assert_swap_topics(&test, &topics, events::SWAP_TOPPED_UP, &test.swap_id);
+    assert_eq!(
+        SwapToppedUp::from_val(&test.env, &data),
+        SwapToppedUp { seq: 2, extra_amount: 150, amount: 750 }
+    );
+
+    assert_eq!(test.token.balance(&test.sender), 250);
+    assert_eq!(test.token.balance(&test.contract.address), 750);
+    assert_eq!(test.contract.get_locked_total(&test.token.address), 750);
+    assert_eq!(test.contract.get_swap(&test.swap_id).unwrap().amount, 750);
+
+    // Recipient receives the topped-up amount
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+    assert_eq!(test.token.balance(&test.recipient), 750);
+    assert_eq!(test.contract.get_locked_total(&test.token.address), 0);
+}
+
+#[test]
+#[should_panic(expected = "timelock expired")]
+fn test_top_up_after_expiry() {
+    let test = HTLCTest::setup();
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &600,
+        &test.hashlock,
+        &12350,
+    );
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12350;
+    });
+
+    test.contract.top_up(&test.swap_id, &150);
+}
+
+#[test]
+#[should_panic(expected = "swap already withdrawn")]
+fn test_top_up_after_withdraw() {
+    let test = HTLCTest::setup();
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &600,
+        &test.hashlock,
+        &12350,
+    );
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+
+    test.contract.top_up(&test.swap_id, &150);
+}
+
+#[test]
+#[should_panic(expected = "amount must be positive")]
+fn test_top_up_invalid_amount() {
+    let test = HTLCTest::setup();
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &600,
+        &test.hashlock,
+        &12350,
+    );
+
+    test.contract.top_up(&test.swap_id, &0);
+}
+
+fn payout_options(env: &Env, shares: &[(Address, u32)]) -> SwapOptions {
+    let mut options = SwapOptions::none(env);
+    for (recipient, bps) in shares {
+        options.payouts.push_back(Payout { recipient: recipient.clone(), bps: *bps });
+    }
+    options
+}
+
+#[test]
+fn test_withdraw_single_payout() {
+    let test = HTLCTest::setup();
+    let beneficiary = Address::generate(&test.env);
+
+    test.create_swap_with_options(800, &payout_options(&test.env, &[(beneficiary.clone(), 10_000)]));
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+
+    assert_eq!(test.token.balance(&beneficiary), 800);
+    assert_eq!(test.token.balance(&test.recipient), 0);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+fn test_withdraw_split_payout_with_dust() {
+    let test = HTLCTest::setup();
+    let integrator = Address::generate(&test.env);
+    let referral = Address::generate(&test.env);
+
+    let options = payout_options(
+        &test.env,
+        &[(test.recipient.clone(), 5_000), (integrator.clone(), 3_000), (referral.clone(), 2_000)],
+    );
+    test.create_swap_with_options(999, &options);
+    assert_eq!(test.contract.get_swap(&test.swap_id).unwrap().payouts, options.payouts);
+
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+
+    // 499 + 299 + 199 = 997, the 2 units of dust go to the first beneficiary
+    assert_eq!(test.token.balance(&test.recipient), 501);
+    assert_eq!(test.token.balance(&integrator), 299);
+    assert_eq!(test.token.balance(&referral), 199);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+fn test_withdraw_max_payouts() {
+    let test = HTLCTest::setup();
+
+    let mut shares = std::vec::Vec::new();
+    for _ in 0..10 {
+        shares.push((Address::generate(&test.env), 1_000));
+    }
+    test.create_swap_with_options(1000, &payout_options(&test.env, &shares));
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+
+    for (beneficiary, _) in shares.iter() {
+        assert_eq!(test.token.balance(beneficiary), 100);
+    }
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+#[should_panic(expected = "payout shares must sum to 10000 bps")]
+fn test_create_swap_payout_shares_not_whole() {
+    let test = HTLCTest::setup();
+    let integrator = Address::generate(&test.env);
+
+    let options = payout_options(&test.env, &[(test.recipient.clone(), 9_000), (integrator, 500)]);
+    test.create_swap_with_options(800, &options);
+}
+
+#[test]
+#[should_panic(expected = "payout share must be positive")]
+fn test_create_swap_payout_zero_share() {
+    let test = HTLCTest::setup();
+    let integrator = Address::generate(&test.env);
+
+    let options = payout_options(&test.env, &[(test.recipient.clone(), 10_000), (integrator, 0)]);
+    test.create_swap_with_options(800, &options);
+}
+
+#[test]
+#[should_panic(expected = "too many payouts")]
+fn test_create_swap_too_many_payouts() {
+    let test = HTLCTest::setup();
+
+    let mut shares = std::vec::Vec::new();
+    for _ in 0..11 {
+        shares.push((Address::generate(&test.env), 1));
+    }
+    test.create_swap_with_options(800, &payout_options(&test.env, &shares));
+}
+
+fn integrator_options(env: &Env, integrator: &Address, bps: u32) -> SwapOptions {
+    let mut options = SwapOptions::none(env);
+    options.integrator = Some(integrator.clone());
+    options.integrator_fee_bps = bps;
+    options
+}
+
+#[test]
+fn test_withdraw_pays_integrator_fee() {
+    let test = HTLCTest::setup();
+    let integrator = Address::generate(&test.env);
+
+    test.set_max_integrator_fee_bps(100);
+
+    test.create_swap_with_options(800, &integrator_options(&test.env, &integrator, 50));
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+
+    // 0.5% of 800
+    assert_eq!(test.token.balance(&integrator), 4);
+    assert_eq!(test.token.balance(&test.recipient), 796);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+    assert_eq!(test.contract.get_integrator_accrued(&integrator, &test.token.address), 4);
+}
+
+#[test]
+fn test_integrator_fee_events_and_accrual() {
+    let test = HTLCTest::setup();
+    let integrator = Address::generate(&test.env);
+    let swap_id_2 = Bytes::from_slice(&test.env, b"test_swap_002");
+
+    test.set_max_integrator_fee_bps(100);
+    test.create_swap_with_options(800, &integrator_options(&test.env, &integrator, 100));
+    test.contract.create_swap_with_options(
+        &swap_id_2,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &200,
+        &test.hashlock,
+        &12350,
+        &integrator_options(&test.env, &integrator, 100),
+    );
+
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+    let events = test.env.events().all();
+    let (_, topics, data) = events
+        .iter()
+        .find(|(contract, topics, _)| {
+            *contract == test.contract.address
+                && Symbol::from_val(&test.env, &topics.get(0).unwrap()) == events::INTEGRATOR_FEE_PAID
+        })
+        .expect("no fee event");
+    assert_swap_topics(&test, &topics, events::INTEGRATOR_FEE_PAID, &test.swap_id);
+    assert_eq!(
+        IntegratorFeePaid::from_val(&test.env, &data),
+        IntegratorFeePaid {
+            seq: 5, // After the scheduled and executed fee cap change
+            integrator: integrator.clone(),
+            token: test.token.address.clone(),
+            amount: 8,
+        }
+    );
+
+    test.contract.withdraw(&swap_id_2, &test.recipient, &test.preimage);
+    assert_eq!(test.contract.get_integrator_accrued(&integrator, &test.token.address), 10);
+}
+
+#[test]
+fn test_integrator_fee_with_payout_split() {
+    let test = HTLCTest::setup();
+    let integrator = Address::generate(&test.env);
+    let referral = Address::generate(&test.env);
+
+    test.set_max_integrator_fee_bps(100);
+    let mut options = payout_options(&test.env, &[(test.recipient.clone(), 7_500), (referral.clone(), 2_500)]);
+    options.integrator = Some(integrator.clone());
+    options.integrator_fee_bps = 100;
+    test.create_swap_with_options(1000, &options);
+
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+
+    // Fee comes off the top, the remaining 990 is split
+    assert_eq!(test.token.balance(&integrator), 10);
+    assert_eq!(test.token.balance(&test.recipient), 743);
+    assert_eq!(test.token.balance(&referral), 247);
+}
+
+#[test]
+fn test_refund_skips_integrator_fee() {
+    let test = HTLCTest::setup();
+    let integrator = Address::generate(&test.env);
+
+    test.set_max_integrator_fee_bps(100);
+    test.create_swap_with_options(800, &integrator_options(&test.env, &integrator, 100));
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12351;
+    });
+    test.contract.refund(&test.swap_id, &test.sender);
+
+    assert_eq!(test.token.balance(&test.sender), 1000);
+    assert_eq!(test.token.balance(&integrator), 0);
+    assert_eq!(test.contract.get_integrator_accrued(&integrator, &test.token.address), 0);
+}
+
+#[test]
+#[should_panic(expected = "integrator fee exceeds cap")]
+fn test_create_swap_integrator_fee_above_cap() {
+    let test = HTLCTest::setup();
+    let integrator = Address::generate(&test.env);
+
+    test.set_max_integrator_fee_bps(100);
+    test.create_swap_with_options(800, &integrator_options(&test.env, &integrator, 101));
+}
+
+#[test]
+#[should_panic(expected = "integrator fee set without integrator")]
+fn test_create_swap_integrator_fee_without_integrator() {
+    let test = HTLCTest::setup();
+
+    test.set_max_integrator_fee_bps(100);
+    let mut options = SwapOptions::none(&test.env);
+    options.integrator_fee_bps = 50;
+    test.create_swap_with_options(800, &options);
+}
+
+#[test]
+#[should_panic(expected = "integrator fee exceeds cap")]
+fn test_integrator_fees_disabled_by_default() {
+    let test = HTLCTest::setup();
+    let integrator = Address::generate(&test.env);
+
+    assert_eq!(test.contract.get_max_integrator_fee_bps(), 0);
+    test.create_swap_with_options(800, &integrator_options(&test.env, &integrator, 1));
+}
+
+mod receivers {
+    use crate::WithdrawReceiver;
+    use soroban_sdk::{contract, contractimpl, symbol_short, token, Address, Bytes, Env};
+
+    #[contract]
+    pub struct MockReceiver;
+
+    #[contractimpl]
+    impl WithdrawReceiver for MockReceiver {
+        fn on_htlc_withdraw(env: Env, swap_id: Bytes, token: Address, amount: i128, data: Bytes) {
+            // Funds must already be here when the hook runs
+            let balance = token::Client::new(&env, &token).balance(&env.current_contract_address());
+            assert!(balance >= amount);
+            env.storage()
+                .instance()
+                .set(&symbol_short!("last"), &(swap_id, token, amount, data));
+        }
+    }
+
+    #[contractimpl]
+    impl MockReceiver {
+        pub fn last_call(env: Env) -> Option<(Bytes, Address, i128, Bytes)> {
+            env.storage().instance().get(&symbol_short!("last"))
+        }
+    }
+
+    pub mod failing {
+        use crate::WithdrawReceiver;
+        use soroban_sdk::{contract, contractimpl, Address, Bytes, Env};
+
+        #[contract]
+        pub struct FailingReceiver;
+
+        #[contractimpl]
+        impl WithdrawReceiver for FailingReceiver {
+            fn on_htlc_withdraw(_env: Env, _swap_id: Bytes, _token: Address, _amount: i128, _data: Bytes) {
+                panic!("receiver rejected funds");
+            }
+        }
+    }
+}
+use receivers::{failing::FailingReceiver, MockReceiver, MockReceiverClient};
+
+fn callback_options(env: &Env, target: &Address, data: &Bytes) -> SwapOptions {
+    let mut options = SwapOptions::none(env);
+    options.callback = Some(target.clone());
+    options.callback_data = data.clone();
+    options
+}
+
+#[test]
+fn test_withdraw_calls_receiver() {
+    let test = HTLCTest::setup();
+    let receiver = MockReceiverClient::new(&test.env, &test.env.register(MockReceiver, ()));
+    let data = Bytes::from_slice(&test.env, b"deposit:vault-1");
+
+    test.create_swap_with_options(800, &callback_options(&test.env, &receiver.address, &data));
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+
+    assert_eq!(test.token.balance(&receiver.address), 800);
+    assert_eq!(test.token.balance(&test.recipient), 0);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+    assert_eq!(
+        receiver.last_call(),
+        Some((test.swap_id.clone(), test.token.address.clone(), 800, data))
+    );
+    assert!(test.contract.get_swap(&test.swap_id).unwrap().is_withdrawn);
+}
+
+#[test]
+fn test_withdraw_callback_after_integrator_fee() {
+    let test = HTLCTest::setup();
+    let receiver = MockReceiverClient::new(&test.env, &test.env.register(MockReceiver, ()));
+    let integrator = Address::generate(&test.env);
+
+    test.set_max_integrator_fee_bps(100);
+    let mut options = callback_options(&test.env, &receiver.address, &Bytes::new(&test.env));
+    options.integrator = Some(integrator.clone());
+    options.integrator_fee_bps = 100;
+    test.create_swap_with_options(800, &options);
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+
+    assert_eq!(test.token.balance(&integrator), 8);
+    assert_eq!(test.token.balance(&receiver.address), 792);
+    assert_eq!(receiver.last_call().unwrap().2, 792);
+}
+
+#[test]
+fn test_withdraw_reverts_when_receiver_fails() {
+    let test = HTLCTest::setup();
+    let receiver = test.env.register(FailingReceiver, ());
+
+    test.create_swap_with_options(800, &callback_options(&test.env, &receiver, &Bytes::new(&test.env)));
+
+    assert!(test.contract.try_withdraw(&test.swap_id, &test.recipient, &test.preimage).is_err());
+
+    // Nothing moved and the swap is still open
+    assert_eq!(test.token.balance(&receiver), 0);
+    assert_eq!(test.token.balance(&test.contract.address), 800);
+    let swap = test.contract.get_swap(&test.swap_id).unwrap();
+    assert!(!swap.is_withdrawn);
+    assert_eq!(test.contract.get_locked_total(&test.token.address), 800);
+}
+
+#[test]
+#[should_panic(expected = "callback cannot be combined with payouts")]
+fn test_create_swap_callback_with_payouts() {
+    let test = HTLCTest::setup();
+    let receiver = test.env.register(MockReceiver, ());
+
+    let mut options = callback_options(&test.env, &receiver, &Bytes::new(&test.env));
+    options.payouts.push_back(Payout { recipient: test.recipient.clone(), bps: 10_000 });
+    test.create_swap_with_options(800, &options);
+}
+
+struct AmmSetup<'a> {
+    token_out: TokenClient<'a>,
+    router: mock_amm::MockAmmClient<'a>,
+}
+
+/// Pool of the swap token against a fresh token, 10000/10000, set as the HTLC router
+fn setup_amm<'a>(test: &HTLCTest<'a>) -> AmmSetup<'a> {
+    let token_admin = Address::generate(&test.env);
+    let (token_out, token_out_admin) = create_token_contract(&test.env, &token_admin);
+    let token_in_admin = TokenAdminClient::new(&test.env, &test.token.address);
+
+    let router = mock_amm::MockAmmClient::new(
+        &test.env,
+        &test.env.register(mock_amm::MockAmm, (&test.token.address, &token_out.address)),
+    );
+
+    let provider = Address::generate(&test.env);
+    token_in_admin.mint(&provider, &10_000);
+    token_out_admin.mint(&provider, &10_000);
+    router.add_liquidity(&provider, &10_000, &10_000);
+
+    test.govern(&Operation::SetRouter(router.address.clone()));
+
+    AmmSetup { token_out, router }
+}
+
+#[test]
+fn test_withdraw_and_swap() {
+    let test = HTLCTest::setup();
+    let amm = setup_amm(&test);
+    let path = vec![&test.env, test.token.address.clone(), amm.token_out.address.clone()];
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+
+    let expected = mock_amm::MockAmm::get_amount_out(800, 10_000, 10_000);
+    let amount_out = test.contract.withdraw_and_swap(
+        &test.swap_id,
+        &test.recipient,
+        &test.preimage,
+        &amm.router.address,
+        &path,
+        &expected,
+    );
+
+    let (topics, data) = test.last_htlc_event();
+    assert_swap_topics(&test, &topics, events::WITHDRAW_SWAPPED, &test.swap_id);
+    assert_eq!(
+        WithdrawSwapped::from_val(&test.env, &data),
+        WithdrawSwapped {
+            // After the two governance events setting the router
+            seq: 5,
+            router: amm.router.address.clone(),
+            token_out: amm.token_out.address.clone(),
+            amount_in: 800,
+            amount_out: expected,
+        }
+    );
+
+    assert_eq!(amount_out, 738);
+    assert_eq!(amm.token_out.balance(&test.recipient), 738);
+    assert_eq!(test.token.balance(&test.recipient), 0);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+    assert_eq!(amm.router.get_reserves(), (10_800, 10_000 - 738));
+
+    let swap = test.contract.get_swap(&test.swap_id).unwrap();
+    assert!(swap.is_withdrawn);
+    assert_eq!(swap.preimage, Some(test.preimage.clone()));
+    assert_eq!(test.contract.get_locked_total(&test.token.address), 0);
+}
+
+#[test]
+fn test_withdraw_and_swap_reverts_below_min_out() {
+    let test = HTLCTest::setup();
+    let amm = setup_amm(&test);
+    let path = vec![&test.env, test.token.address.clone(), amm.token_out.address.clone()];
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+
+    let result = test.contract.try_withdraw_and_swap(
+        &test.swap_id,
+        &test.recipient,
+        &test.preimage,
+        &amm.router.address,
+        &path,
+        &739,
+    );
+    assert!(result.is_err());
+
+    // Swap remains claimable and nothing moved
+    assert_eq!(test.token.balance(&test.contract.address), 800);
+    assert_eq!(test.token.balance(&test.recipient), 0);
+    assert_eq!(amm.token_out.balance(&test.recipient), 0);
+    assert!(!test.contract.get_swap(&test.swap_id).unwrap().is_withdrawn);
+}
+
+#[test]
+#[should_panic(expected = "router not allowed")]
+fn test_withdraw_and_swap_unknown_router() {
+    let test = HTLCTest::setup();
+    let amm = setup_amm(&test);
+    let rogue = test
+        .env
+        .register(mock_amm::MockAmm, (&test.token.address, &amm.token_out.address));
+    let path = vec![&test.env, test.token.address.clone(), amm.token_out.address.clone()];
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+
+    test.contract.withdraw_and_swap(&test.swap_id, &test.recipient, &test.preimage, &rogue, &path, &1);
+}
+
+#[test]
+#[should_panic(expected = "path must start with the swap token")]
+fn test_withdraw_and_swap_wrong_path() {
+    let test = HTLCTest::setup();
+    let amm = setup_amm(&test);
+    let path = vec![&test.env, amm.token_out.address.clone(), test.token.address.clone()];
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+
+    test.contract.withdraw_and_swap(
+        &test.swap_id,
+        &test.recipient,
+        &test.preimage,
+        &amm.router.address,
+        &path,
+        &1,
+    );
+}
+
+#[test]
+#[should_panic(expected = "swap has a custom payout")]
+fn test_withdraw_and_swap_with_payouts() {
+    let test = HTLCTest::setup();
+    let amm = setup_amm(&test);
+    let path = vec![&test.env, test.token.address.clone(), amm.token_out.address.clone()];
+
+    test.create_swap_with_options(800, &payout_options(&test.env, &[(test.recipient.clone(), 10_000)]));
+
+    test.contract.withdraw_and_swap(
+        &test.swap_id,
+        &test.recipient,
+        &test.preimage,
+        &amm.router.address,
+        &path,
+        &1,
+    );
+}
+
+fn group_legs(test: &HTLCTest, legs: &[(&[u8], &Address, i128)]) -> Vec<GroupLeg> {
+    let mut result = Vec::new(&test.env);
+    for (swap_id, recipient, amount) in legs {
+        result.push_back(GroupLeg {
+            swap_id: Bytes::from_slice(&test.env, swap_id),
+            recipient: (*recipient).clone(),
+            token: test.token.address.clone(),
+            amount: *amount,
+        });
+    }
+    result
+}
+
+#[test]
+fn test_withdraw_group_releases_every_leg() {
+    let test = HTLCTest::setup();
+    let group_id = Bytes::from_slice(&test.env, b"group_001");
+    let second = Address::generate(&test.env);
+    let third = Address::generate(&test.env);
+    let legs = group_legs(
+        &test,
+        &[(b"leg_1", &test.recipient, 300), (b"leg_2", &second, 200), (b"leg_3", &third, 100)],
+    );
+
+    test.contract.create_swap_group(&group_id, &test.sender, &test.hashlock, &12350, &legs);
+
+    let (topics, data) = test.last_htlc_event();
+    assert_eq!(Symbol::from_val(&test.env, &topics.get(0).unwrap()), events::SWAP_GROUP_CREATED);
+    assert_eq!(Bytes::from_val(&test.env, &topics.get(2).unwrap()), group_id);
+    let created = SwapGroupCreated::from_val(&test.env, &data);
+    assert_eq!(created.seq, 4);
+    assert_eq!(created.swap_ids.len(), 3);
+
+    assert_eq!(test.token.balance(&test.contract.address), 600);
+    let group = test.contract.get_group(&group_id).unwrap();
+    assert_eq!(group.sender, test.sender);
+    assert_eq!(group.swap_ids, created.swap_ids);
+    let leg = test.contract.get_swap(&group.swap_ids.get(1).unwrap()).unwrap();
+    assert_eq!(leg.recipient, second);
+    assert_eq!(leg.group_id, Some(group_id.clone()));
+
+    // One call with the preimage, no recipient signatures needed
+    test.contract.withdraw_group(&group_id, &test.preimage);
+    assert_eq!(test.env.auths().len(), 0);
+
+    assert_eq!(test.token.balance(&test.recipient), 300);
+    assert_eq!(test.token.balance(&second), 200);
+    assert_eq!(test.token.balance(&third), 100);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+    for swap_id in group.swap_ids.iter() {
+        assert!(test.contract.get_swap(&swap_id).unwrap().is_withdrawn);
+    }
+}
+
+#[test]
+fn test_withdraw_group_skips_resolved_legs() {
+    let test = HTLCTest::setup();
+    let group_id = Bytes::from_slice(&test.env, b"group_001");
+    let second = Address::generate(&test.env);
+    let legs = group_legs(&test, &[(b"leg_1", &test.recipient, 300), (b"leg_2", &second, 200)]);
+
+    test.contract.create_swap_group(&group_id, &test.sender, &test.hashlock, &12350, &legs);
+
+    // First recipient claims its leg on its own
+    let leg_1 = Bytes::from_slice(&test.env, b"leg_1");
+    test.contract.withdraw(&leg_1, &test.recipient, &test.preimage);
+
+    test.contract.withdraw_group(&group_id, &test.preimage);
+    assert_eq!(test.token.balance(&test.recipient), 300);
+    assert_eq!(test.token.balance(&second), 200);
+
+    // Nothing left to release
+    assert!(test.contract.try_withdraw_group(&group_id, &test.preimage).is_err());
+}
+
+#[test]
+#[should_panic(expected = "invalid preimage")]
+fn test_withdraw_group_invalid_preimage() {
+    let test = HTLCTest::setup();
+    let group_id = Bytes::from_slice(&test.env, b"group_001");
+    let legs = group_legs(&test, &[(b"leg_1", &test.recipient, 300)]);
+
+    test.contract.create_swap_group(&group_id, &test.sender, &test.hashlock, &12350, &legs);
+
+    let wrong_preimage = Bytes::from_slice(&test.env, b"wrong_preimage");
+    test.contract.withdraw_group(&group_id, &wrong_preimage);
+}
+
+#[test]
+fn test_refund_group_after_expiry() {
+    let test = HTLCTest::setup();
+    let group_id = Bytes::from_slice(&test.env, b"group_001");
+    let second = Address::generate(&test.env);
+    let legs = group_legs(&test, &[(b"leg_1", &test.recipient, 300), (b"leg_2", &second, 200)]);
+
+    test.contract.create_swap_group(&group_id, &test.sender, &test.hashlock, &12350, &legs);
+
+    assert!(test.contract.try_refund_group(&group_id).is_err());
+
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12351;
+    });
+    test.contract.refund_group(&group_id);
+
+    // Verify sender authorization was required
+    assert_eq!(test.env.auths().len(), 1);
+    assert_eq!(test.env.auths()[0].0, test.sender);
+
+    assert_eq!(test.token.balance(&test.sender), 1000);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+    assert_eq!(test.contract.get_locked_total(&test.token.address), 0);
+    assert!(test.contract.try_withdraw_group(&group_id, &test.preimage).is_err());
+}
+
+#[test]
+#[should_panic(expected = "group legs share the group timelock")]
+fn test_extend_timelock_of_group_leg() {
+    let test = HTLCTest::setup();
+    let group_id = Bytes::from_slice(&test.env, b"group_001");
+    let legs = group_legs(&test, &[(b"leg_1", &test.recipient, 300)]);
+    test.contract.create_swap_group(&group_id, &test.sender, &test.hashlock, &12350, &legs);
+
+    test.contract.extend_timelock(&Bytes::from_slice(&test.env, b"leg_1"), &12400);
+}
+
+#[test]
+#[should_panic(expected = "too many group legs")]
+fn test_create_swap_group_too_many_legs() {
+    let test = HTLCTest::setup();
+    let group_id = Bytes::from_slice(&test.env, b"group_001");
+
+    let mut legs = Vec::new(&test.env);
+    for i in 0..11u8 {
+        legs.push_back(GroupLeg {
+            swap_id: Bytes::from_slice(&test.env, &[b'l', i]),
+            recipient: test.recipient.clone(),
+            token: test.token.address.clone(),
+            amount: 10,
+        });
+    }
+
+    test.contract.create_swap_group(&group_id, &test.sender, &test.hashlock, &12350, &legs);
+}
+
+#[test]
+#[should_panic(expected = "group_id already exists")]
+fn test_create_swap_group_duplicate_id() {
+    let test = HTLCTest::setup();
+    let group_id = Bytes::from_slice(&test.env, b"group_001");
+
+    let legs = group_legs(&test, &[(b"leg_1", &test.recipient, 300)]);
+    test.contract.create_swap_group(&group_id, &test.sender, &test.hashlock, &12350, &legs);
+
+    let legs = group_legs(&test, &[(b"leg_2", &test.recipient, 300)]);
+    test.contract.create_swap_group(&group_id, &test.sender, &test.hashlock, &12350, &legs);
+}
+
+#[test]
+fn test_withdraw_records_preimage() {
+    let test = HTLCTest::setup();
+
+    assert_eq!(test.contract.get_preimage(&test.hashlock), None);
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+
+    assert_eq!(test.contract.get_preimage(&test.hashlock), Some(test.preimage.clone()));
+}
+
+#[test]
+fn test_withdraw_revealed_after_sibling_withdraw() {
+    let test = HTLCTest::setup();
+    let other_recipient = Address::generate(&test.env);
+    let swap_id_2 = Bytes::from_slice(&test.env, b"test_swap_002");
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &400,
+        &test.hashlock,
+        &12350,
+    );
+    test.contract.create_swap(
+        &swap_id_2,
+        &test.sender,
+        &other_recipient,
+        &test.token.address,
+        &300,
+        &test.hashlock,
+        &12350,
+    );
+
+    // Not claimable before the secret is out
+    assert!(test.contract.try_withdraw_revealed(&swap_id_2).is_err());
+
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+    test.contract.withdraw_revealed(&swap_id_2);
+
+    // No signature needed, funds still go to the swap's recipient
+    assert_eq!(test.env.auths().len(), 0);
+    assert_eq!(test.token.balance(&other_recipient), 300);
+    let swap = test.contract.get_swap(&swap_id_2).unwrap();
+    assert!(swap.is_withdrawn);
+    assert_eq!(swap.preimage, Some(test.preimage.clone()));
+}
+
+#[test]
+fn test_reveal_publishes_preimage() {
+    let test = HTLCTest::setup();
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+
+    let hashlock = test.contract.reveal(&test.preimage);
+    assert_eq!(hashlock, test.hashlock);
+
+    let (topics, data) = test.last_htlc_event();
+    assert_eq!(topics.len(), 3);
+    assert_eq!(Symbol::from_val(&test.env, &topics.get(0).unwrap()), events::PREIMAGE_REVEALED);
+    assert_eq!(Bytes::from_val(&test.env, &topics.get(2).unwrap()), test.hashlock);
+    assert_eq!(
+        PreimageRevealed::from_val(&test.env, &data),
+        PreimageRevealed { seq: 2, preimage: test.preimage.clone() }
+    );
+
+    // Revealing again is a no-op
+    test.contract.reveal(&test.preimage);
+    assert_eq!(test.contract.get_event_seq(), 2);
+
+    test.contract.withdraw_revealed(&test.swap_id);
+    assert_eq!(test.token.balance(&test.recipient), 800);
+}
+
+#[test]
+#[should_panic(expected = "timelock expired")]
+fn test_withdraw_revealed_after_expiry() {
+    let test = HTLCTest::setup();
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+    test.contract.reveal(&test.preimage);
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12350;
+    });
+
+    test.contract.withdraw_revealed(&test.swap_id);
+}
+
+fn premium_options(env: &Env, premium: i128) -> SwapOptions {
+    let mut options = SwapOptions::none(env);
+    options.premium = premium;
+    options
+}
+
+#[test]
+fn test_premium_locked_at_create() {
+    let test = HTLCTest::setup();
+
+    test.create_swap_with_options(800, &premium_options(&test.env, 50));
+
+    assert_eq!(test.token.balance(&test.sender), 150);
+    assert_eq!(test.token.balance(&test.contract.address), 850);
+    assert_eq!(test.contract.get_locked_total(&test.token.address), 850);
+    let swap = test.contract.get_swap(&test.swap_id).unwrap();
+    assert_eq!(swap.amount, 800);
+    assert_eq!(swap.premium, 50);
+}
+
+#[test]
+fn test_premium_returned_to_sender_on_withdraw() {
+    let test = HTLCTest::setup();
+
+    test.create_swap_with_options(800, &premium_options(&test.env, 50));
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+
+    let events = test.env.events().all();
+    let (_, topics, data) = events.get(events.len() - 2).unwrap();
+    assert_eq!(Symbol::from_val(&test.env, &topics.get(0).unwrap()), events::PREMIUM_PAID);
+    assert_eq!(
+        PremiumPaid::from_val(&test.env, &data),
+        PremiumPaid { seq: 2, to: test.sender.clone(), amount: 50 }
+    );
+
+    assert_eq!(test.token.balance(&test.recipient), 800);
+    assert_eq!(test.token.balance(&test.sender), 200);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+    assert_eq!(test.contract.get_locked_total(&test.token.address), 0);
+}
+
+#[test]
+fn test_premium_paid_to_recipient_on_refund() {
+    let test = HTLCTest::setup();
+
+    test.create_swap_with_options(800, &premium_options(&test.env, 50));
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12350;
+    });
+    test.contract.refund(&test.swap_id, &test.sender);
+
+    let events = test.env.events().all();
+    let (_, _, data) = events.get(events.len() - 2).unwrap();
+    assert_eq!(
+        PremiumPaid::from_val(&test.env, &data),
+        PremiumPaid { seq: 2, to: test.recipient.clone(), amount: 50 }
+    );
+
+    assert_eq!(test.token.balance(&test.recipient), 50);
+    assert_eq!(test.token.balance(&test.sender), 950);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+    assert_eq!(test.contract.get_locked_total(&test.token.address), 0);
+}
+
+#[test]
+fn test_premium_returned_to_sender_when_recipient_denied() {
+    let test = HTLCTest::setup();
+
+    test.create_swap_with_options(800, &premium_options(&test.env, 50));
+    test.contract.deny(&test.recipient);
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12350;
+    });
+    test.contract.refund(&test.swap_id, &test.sender);
+
+    let events = test.env.events().all();
+    let (_, _, data) = events.get(events.len() - 2).unwrap();
+    assert_eq!(
+        PremiumPaid::from_val(&test.env, &data),
+        PremiumPaid { seq: 3, to: test.sender.clone(), amount: 50 }
+    );
+    assert_eq!(test.token.balance(&test.recipient), 0);
+    assert_eq!(test.token.balance(&test.sender), 1000);
+}
+
+#[test]
+fn test_premium_returned_to_sender_on_cancel() {
+    let test = HTLCTest::setup();
+
+    test.create_swap_with_options(800, &premium_options(&test.env, 50));
+    test.contract.cancel(&test.swap_id);
+
+    assert_eq!(test.token.balance(&test.recipient), 0);
+    assert_eq!(test.token.balance(&test.sender), 1000);
+    assert_eq!(test.contract.get_locked_total(&test.token.address), 0);
+}
+
+#[test]
+fn test_no_premium_event_without_premium() {
+    let test = HTLCTest::setup();
+
+    test.create_swap_with_options(800, &SwapOptions::none(&test.env));
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+
+    assert_eq!(test.contract.get_event_seq(), 2);
+}
+
+#[test]
+#[should_panic(expected = "premium cannot be negative")]
+fn test_negative_premium() {
+    let test = HTLCTest::setup();
+
+    test.create_swap_with_options(800, &premium_options(&test.env, -1));
+}
+
+fn bond_resolver(test: &HTLCTest, resolver: &Address, amount: i128) {
+    if test.contract.get_bond_token().is_none() {
+        test.contract.set_bond_token(&test.admin, &test.token.address);
+    }
+    test.contract.register_resolver(resolver);
+    test.contract.stake(resolver, &amount);
+}
+
+fn resolver_options(env: &Env, resolver_sender: bool, resolver_claims: bool) -> SwapOptions {
+    let mut options = SwapOptions::none(env);
+    options.resolver_sender = resolver_sender;
+    options.resolver_claims = resolver_claims;
+    options
+}
+
+#[test]
+fn test_resolver_bond_lifecycle() {
+    let test = HTLCTest::setup();
+    let resolver = Address::generate(&test.env);
+    TokenAdminClient::new(&test.env, &test.token.address).mint(&resolver, &500);
+
+    test.govern(&Operation::SetMinBond(100));
+    bond_resolver(&test, &resolver, 300);
+    assert_eq!(test.env.auths()[0].0, resolver);
+    assert!(test.contract.is_bonded_resolver(&resolver));
+    assert_eq!(test.token.balance(&resolver), 200);
+    // Bonds count as liabilities so `skim` can't take them
+    assert_eq!(test.contract.get_locked_total(&test.token.address), 300);
+
+    test.contract.unbond(&resolver, &250);
+    let (topics, data) = test.last_htlc_event();
+    assert_eq!(Symbol::from_val(&test.env, &topics.get(0).unwrap()), events::RESOLVER_BOND);
+    assert_eq!(Address::from_val(&test.env, &topics.get(2).unwrap()), resolver);
+    assert_eq!(
+        ResolverBondUpdated::from_val(&test.env, &data).unbonding,
+        250
+    );
+
+    // Below the minimum bond while unbonding
+    assert!(!test.contract.is_bonded_resolver(&resolver));
+    assert!(test.contract.try_withdraw_bond(&resolver).is_err());
+
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12345 + DEFAULT_UNBOND_DELAY;
+    });
+    assert_eq!(test.contract.withdraw_bond(&resolver), 250);
+    assert_eq!(test.token.balance(&resolver), 450);
+    assert_eq!(test.contract.get_locked_total(&test.token.address), 50);
+    assert_eq!(
+        test.contract.get_resolver(&resolver),
+        Some(Resolver { bond: 50, unbonding: 0, unbond_at: 0 })
+    );
+}
+
+#[test]
+#[should_panic(expected = "resolver already registered")]
+fn test_register_resolver_twice() {
+    let test = HTLCTest::setup();
+    let resolver = Address::generate(&test.env);
+
+    test.contract.register_resolver(&resolver);
+    test.contract.register_resolver(&resolver);
+}
+
+#[test]
+#[should_panic(expected = "bond token already set")]
+fn test_bond_token_set_once() {
+    let test = HTLCTest::setup();
+    let other_token = Address::generate(&test.env);
+
+    test.contract.set_bond_token(&test.admin, &test.token.address);
+    test.contract.set_bond_token(&test.admin, &other_token);
+}
+
+#[test]
+#[should_panic(expected = "resolver not bonded")]
+fn test_resolver_sender_requires_bond() {
+    let test = HTLCTest::setup();
+
+    test.create_swap_with_options(800, &resolver_options(&test.env, true, false));
+}
+
+#[test]
+fn test_resolver_claims_swap() {
+    let test = HTLCTest::setup();
+    let resolver = Address::generate(&test.env);
+    let outsider = Address::generate(&test.env);
+    TokenAdminClient::new(&test.env, &test.token.address).mint(&resolver, &100);
+
+    bond_resolver(&test, &resolver, 100);
+    test.create_swap_with_options(800, &resolver_options(&test.env, false, true));
+    test.contract.reveal(&test.preimage);
+
+    assert!(test.contract.try_withdraw_revealed(&test.swap_id).is_err());
+    assert!(test
+        .contract
+        .try_resolver_withdraw(&test.swap_id, &outsider, &test.preimage)
+        .is_err());
+
+    test.contract.resolver_withdraw(&test.swap_id, &resolver, &test.preimage);
+    assert_eq!(test.env.auths()[0].0, resolver);
+    assert_eq!(test.token.balance(&test.recipient), 800);
+    assert_eq!(test.token.balance(&resolver), 0);
+}
+
+#[test]
+fn test_slash_refunded_resolver_swap() {
+    let test = HTLCTest::setup();
+
+    bond_resolver(&test, &test.sender, 150);
+    test.create_swap_with_options(800, &resolver_options(&test.env, true, false));
+    test.contract.unbond(&test.sender, &100);
+
+    // Open swaps can't be slashed before they expire
+    assert!(test.contract.try_slash(&test.swap_id, &120).is_err());
+
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12350;
+    });
+    test.contract.refund(&test.swap_id, &test.sender);
+
+    // Takes the active bond first, then the unbonding stake
+    assert_eq!(test.contract.slash(&test.swap_id, &120), 120);
+    assert_eq!(test.env.auths()[0].0, test.admin);
+    let (topics, data) = test.last_htlc_event();
+    assert_swap_topics(&test, &topics, events::RESOLVER_SLASHED, &test.swap_id);
+    assert_eq!(
+        ResolverSlashed::from_val(&test.env, &data),
+        ResolverSlashed {
+            seq: 7,
+            resolver: test.sender.clone(),
+            to: test.recipient.clone(),
+            amount: 120,
+        }
+    );
+
+    assert_eq!(test.token.balance(&test.recipient), 120);
+    assert_eq!(test.contract.get_locked_total(&test.token.address), 30);
+    let state = test.contract.get_resolver(&test.sender).unwrap();
+    assert_eq!((state.bond, state.unbonding), (0, 30));
+
+    assert!(test.contract.try_slash(&test.swap_id, &10).is_err());
+}
+
+#[test]
+fn test_slash_to_denied_recipient() {
+    let test = HTLCTest::setup();
+
+    bond_resolver(&test, &test.sender, 150);
+    test.create_swap_with_options(800, &resolver_options(&test.env, true, false));
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12350;
+    });
+    test.contract.refund(&test.swap_id, &test.sender);
+
+    test.contract.deny(&test.recipient);
+    assert!(test.contract.try_slash(&test.swap_id, &100).is_err());
+    assert_eq!(test.token.balance(&test.recipient), 0);
+
+    // Slashable once the recipient is allowed again
+    test.contract.allow(&test.recipient);
+    assert_eq!(test.contract.slash(&test.swap_id, &100), 100);
+}
+
+#[test]
+fn test_resolver_cannot_unbond_ahead_of_swap() {
+    let test = HTLCTest::setup();
+    let timelock = 12345 + 2 * DEFAULT_UNBOND_DELAY;
+
+    bond_resolver(&test, &test.sender, 150);
+    test.contract.create_swap_with_options(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &timelock,
+        &resolver_options(&test.env, true, false),
+    );
+    test.contract.unbond(&test.sender, &150);
+
+    // The unbond delay passed, but the swap hasn't even expired
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12345 + DEFAULT_UNBOND_DELAY;
+    });
+    assert!(test.contract.try_withdraw_bond(&test.sender).is_err());
+
+    // Once expired the swap is slashable without waiting for the resolver to refund
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = timelock;
+    });
+    assert!(test.contract.try_withdraw_bond(&test.sender).is_err());
+    assert_eq!(test.contract.slash(&test.swap_id, &100), 100);
+    assert_eq!(test.token.balance(&test.recipient), 100);
+
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = timelock + DEFAULT_UNBOND_DELAY;
+    });
+    assert_eq!(test.contract.withdraw_bond(&test.sender), 50);
+}
+
+#[test]
+#[should_panic(expected = "swap not funded by a resolver")]
+fn test_slash_plain_swap() {
+    let test = HTLCTest::setup();
+
+    bond_resolver(&test, &test.sender, 100);
+    test.create_swap_with_options(800, &SwapOptions::none(&test.env));
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12350;
+    });
+    test.contract.refund(&test.swap_id, &test.sender);
+
+    test.contract.slash(&test.swap_id, &50);
+}
+
+#[test]
+fn test_participant_stats() {
+    let test = HTLCTest::setup();
+    let swap_id_2 = Bytes::from_slice(&test.env, b"test_swap_002");
+    let swap_id_3 = Bytes::from_slice(&test.env, b"test_swap_003");
+
+    let empty = test.contract.get_participant_stats(&test.sender);
+    assert_eq!((empty.created, empty.withdrawn, empty.refunded, empty.cancelled), (0, 0, 0, 0));
+    assert!(empty.volume.is_empty());
+
+    for (swap_id, amount) in [(&test.swap_id, 300), (&swap_id_2, 200), (&swap_id_3, 100)] {
+        test.contract.create_swap(
+            swap_id,
+            &test.sender,
+            &test.recipient,
+            &test.token.address,
+            &amount,
+            &test.hashlock,
+            &12350,
+        );
+    }
+    test.contract.top_up(&test.swap_id, &50);
+
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+    test.contract.cancel(&swap_id_2);
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12350;
+    });
+    test.contract.refund(&swap_id_3, &test.sender);
+
+    let sender_stats = test.contract.get_participant_stats(&test.sender);
+    assert_eq!(
+        (sender_stats.created, sender_stats.withdrawn, sender_stats.refunded, sender_stats.cancelled),
+        (3, 1, 1, 1)
+    );
+    assert_eq!(sender_stats.volume.get(test.token.address.clone()), Some(650));
+
+    // The recipient shares the outcomes but created nothing
+    let recipient_stats = test.contract.get_participant_stats(&test.recipient);
+    assert_eq!(
+        (recipient_stats.created, recipient_stats.withdrawn, recipient_stats.refunded, recipient_stats.cancelled),
+        (0, 1, 1, 1)
+    );
+    assert!(recipient_stats.volume.is_empty());
+}
+
+fn post_test_order(test: &HTLCTest, order_id: &Bytes) {
+    // 2.0 falling to 1.5 destination units per token over 100 seconds
+    let auction = DutchAuction {
+        start_rate: 2 * RATE_SCALE,
+        end_rate: 15 * RATE_SCALE / 10,
+        start: 12345,
+        duration: 100,
+    };
+    test.contract.post_order(
+        order_id,
+        &test.sender,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &auction,
+        &3600,
+    );
+}
+
+#[test]
+fn test_order_rate_decays() {
+    let test = HTLCTest::setup();
+    let order_id = Bytes::from_slice(&test.env, b"order_001");
+
+    post_test_order(&test, &order_id);
+    assert_eq!(test.env.auths()[0].0, test.sender);
+    assert_eq!(test.token.balance(&test.contract.address), 800);
+    assert_eq!(test.contract.get_locked_total(&test.token.address), 800);
+
+    assert_eq!(test.contract.get_order_rate(&order_id), 2 * RATE_SCALE);
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12345 + 40;
+    });
+    assert_eq!(test.contract.get_order_rate(&order_id), 18 * RATE_SCALE / 10);
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12345 + 500;
+    });
+    assert_eq!(test.contract.get_order_rate(&order_id), 15 * RATE_SCALE / 10);
+}
+
+#[test]
+fn test_fill_order_creates_swap() {
+    let test = HTLCTest::setup();
+    let order_id = Bytes::from_slice(&test.env, b"order_001");
+    let resolver = Address::generate(&test.env);
+    TokenAdminClient::new(&test.env, &test.token.address).mint(&resolver, &100);
+    bond_resolver(&test, &resolver, 100);
+
+    post_test_order(&test, &order_id);
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12345 + 50;
+    });
+
+    // 800 tokens at 1.75
+    assert_eq!(test.contract.fill_order(&order_id, &resolver, &test.swap_id), 1400);
+    assert_eq!(test.env.auths()[0].0, resolver);
+
+    let (topics, data) = test.last_htlc_event();
+    assert_eq!(Symbol::from_val(&test.env, &topics.get(0).unwrap()), events::ORDER_FILLED);
+    assert_eq!(Bytes::from_val(&test.env, &topics.get(2).unwrap()), order_id);
+    assert_eq!(
+        OrderFilled::from_val(&test.env, &data),
+        OrderFilled {
+            seq: 5,
+            resolver: resolver.clone(),
+            swap_id: test.swap_id.clone(),
+            rate: 175 * RATE_SCALE / 100,
+            taking_amount: 1400,
+        }
+    );
+
+    let swap = test.contract.get_swap(&test.swap_id).unwrap();
+    assert_eq!(swap.sender, test.sender);
+    assert_eq!(swap.recipient, resolver);
+    assert_eq!(swap.amount, 800);
+    assert_eq!(swap.timelock, 12345 + 50 + 3600);
+    let order = test.contract.get_order(&order_id).unwrap();
+    assert_eq!(order.swap_id, Some(test.swap_id.clone()));
+    assert_eq!(order.fill_rate, 175 * RATE_SCALE / 100);
+    assert_eq!(order.taking_amount, 1400);
+
+    // The escrow moved into the swap without new transfers
+    assert_eq!(test.token.balance(&test.contract.address), 900);
+    assert_eq!(test.contract.get_locked_total(&test.token.address), 900);
+
+    let other_swap_id = Bytes::from_slice(&test.env, b"test_swap_002");
+    assert!(test.contract.try_fill_order(&order_id, &resolver, &other_swap_id).is_err());
+
+    test.contract.withdraw(&test.swap_id, &resolver, &test.preimage);
+    assert_eq!(test.token.balance(&resolver), 800);
+}
+
+#[test]
+fn test_cancel_order() {
+    let test = HTLCTest::setup();
+    let order_id = Bytes::from_slice(&test.env, b"order_001");
+    let resolver = Address::generate(&test.env);
+    TokenAdminClient::new(&test.env, &test.token.address).mint(&resolver, &100);
+    bond_resolver(&test, &resolver, 100);
+
+    post_test_order(&test, &order_id);
+    test.contract.cancel_order(&order_id);
+    assert_eq!(test.env.auths()[0].0, test.sender);
+    assert_eq!(test.token.balance(&test.sender), 1000);
+    assert_eq!(test.contract.get_locked_total(&test.token.address), 100);
+    assert!(test.contract.get_order(&order_id).unwrap().is_cancelled);
+
+    assert!(test.contract.try_fill_order(&order_id, &resolver, &test.swap_id).is_err());
+}
+
+#[test]
+#[should_panic(expected = "resolver not bonded")]
+fn test_fill_order_requires_bonded_resolver() {
+    let test = HTLCTest::setup();
+    let order_id = Bytes::from_slice(&test.env, b"order_001");
+    let filler = Address::generate(&test.env);
+
+    post_test_order(&test, &order_id);
+    test.contract.fill_order(&order_id, &filler, &test.swap_id);
+}
+
+#[test]
+#[should_panic(expected = "start rate below end rate")]
+fn test_post_order_rising_rate() {
+    let test = HTLCTest::setup();
+    let auction = DutchAuction {
+        start_rate: RATE_SCALE,
+        end_rate: 2 * RATE_SCALE,
+        start: 12345,
+        duration: 100,
+    };
+
+    test.contract.post_order(
+        &Bytes::from_slice(&test.env, b"order_001"),
+        &test.sender,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &auction,
+        &3600,
+    );
+}
+
+/// 2-of-3 committee of locally generated keys: two ed25519, one secp256k1
+struct TestCommittee {
+    ed25519: [ed25519_dalek::SigningKey; 2],
+    secp256k1: k256::ecdsa::SigningKey,
+}
+
+impl TestCommittee {
+    fn new() -> Self {
+        TestCommittee {
+            ed25519: [
+                ed25519_dalek::SigningKey::from_bytes(&[1; 32]),
+                ed25519_dalek::SigningKey::from_bytes(&[2; 32]),
+            ],
+            secp256k1: k256::ecdsa::SigningKey::from_bytes(&[3; 32].into()).unwrap(),
+        }
+    }
+
+    fn committee(&self, env: &Env) -> Committee {
+        let point = self.secp256k1.verifying_key().to_encoded_point(false);
+        Committee {
+            signers: vec![
+                env,
+                Signer::Ed25519(BytesN::from_array(env, &self.ed25519[0].verifying_key().to_bytes())),
+                Signer::Ed25519(BytesN::from_array(env, &self.ed25519[1].verifying_key().to_bytes())),
+                Signer::Secp256k1(BytesN::from_array(env, point.as_bytes().try_into().unwrap())),
+            ],
+            threshold: 2,
+        }
+    }
+
+    fn attest(&self, env: &Env, index: u32, digest: &BytesN<32>) -> Attestation {
+        use ed25519_dalek::Signer as _;
+
+        let digest = digest.to_array();
+        let (signature, recovery_id) = match index {
+            0 | 1 => (self.ed25519[index as usize].sign(&digest).to_bytes(), 0),
+            _ => {
+                let (signature, recovery_id) = self.secp256k1.sign_prehash_recoverable(&digest).unwrap();
+                (signature.to_bytes().into(), recovery_id.to_byte() as u32)
+            }
+        };
+        Attestation { signer: index, signature: BytesN::from_array(env, &signature), recovery_id }
+    }
+}
+
+fn evm_funding(test: &HTLCTest) -> EvmFunding {
+    EvmFunding {
+        chain_id: 11155111,
+        contract: BytesN::from_array(&test.env, &[0xaa; 20]),
+        swap_id: BytesN::from_array(&test.env, &[0xbb; 32]),
+        hashlock: BytesN::try_from(test.hashlock.clone()).unwrap(),
+        amount: U256::from_u32(&test.env, 800),
+        timelock: 20000,
+    }
+}
+
+impl HTLCTest<'_> {
+    /// Require attestations from `committee` and register the EVM contract of
+    /// `evm_funding`, whose token has the same decimals as the test token
+    fn enable_attestation(&self, committee: &TestCommittee) {
+        self.govern(&Operation::SetAttestationCommittee(committee.committee(&self.env)));
+        self.govern(&Operation::SetFundingAsset(
+            11155111,
+            BytesN::from_array(&self.env, &[0xaa; 20]),
+            FundingAsset { token: self.token.address.clone(), evm_decimals: 7 },
+        ));
+    }
+
+    /// Attest `funding` for the test swap id, sender and recipient
+    fn attest_funding(&self, committee: &TestCommittee, funding: &EvmFunding, indexes: &[u32]) -> Vec<Attestation> {
+        let digest = self.contract.get_funding_digest(funding, &self.swap_id, &self.sender, &self.recipient);
+        let mut attestations = Vec::new(&self.env);
+        for &index in indexes {
+            attestations.push_back(committee.attest(&self.env, index, &digest));
+        }
+        attestations
+    }
+
+    fn create_attested_swap(&self, swap_id: &Bytes, timelock: u64, funding: &EvmFunding, attestations: &Vec<Attestation>) {
+        self.contract.create_attested_swap(
+            swap_id,
+            &self.sender,
+            &self.recipient,
+            &self.token.address,
+            &800,
+            &self.hashlock,
+            &timelock,
+            &SwapOptions::none(&self.env),
+            funding,
+            attestations,
+        );
+    }
+}
+
+#[test]
+fn test_create_attested_swap() {
+    let test = HTLCTest::setup();
+    let committee = TestCommittee::new();
+    test.enable_attestation(&committee);
+
+    let funding = evm_funding(&test);
+    let attestations = test.attest_funding(&committee, &funding, &[1, 2]);
+    test.create_attested_swap(&test.swap_id, 12350, &funding, &attestations);
+
+    let (topics, data) = test.last_htlc_event();
+    assert_swap_topics(&test, &topics, events::FUNDING_ATTESTED, &test.swap_id);
+    assert_eq!(
+        FundingAttested::from_val(&test.env, &data),
+        FundingAttested {
+            // After the four governance events enabling attestation and the created event
+            seq: 6,
+            chain_id: 11155111,
+            contract: funding.contract.clone(),
+            evm_swap_id: funding.swap_id.clone(),
+        }
+    );
+    assert_eq!(test.contract.get_swap_funding(&test.swap_id), Some(funding.clone()));
+    assert_eq!(test.token.balance(&test.contract.address), 800);
+
+    // One EVM funding backs a single swap
+    let swap_id_2 = Bytes::from_slice(&test.env, b"test_swap_002");
+    assert!(test
+        .contract
+        .try_create_attested_swap(
+            &swap_id_2,
+            &test.sender,
+            &test.recipient,
+            &test.token.address,
+            &100,
+            &test.hashlock,
+            &12350,
+            &SwapOptions::none(&test.env),
+            &funding,
+            &attestations,
+        )
+        .is_err());
+}
+
+#[test]
+#[should_panic(expected = "EVM funding attestation required")]
+fn test_create_swap_requires_attestation() {
+    let test = HTLCTest::setup();
+    test.govern(&Operation::SetAttestationCommittee(TestCommittee::new().committee(&test.env)));
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+}
+
+#[test]
+fn test_swap_group_requires_attestation() {
+    let test = HTLCTest::setup();
+    test.govern(&Operation::SetAttestationCommittee(TestCommittee::new().committee(&test.env)));
+    let group_id = Bytes::from_slice(&test.env, b"group_001");
+    let legs = group_legs(&test, &[(b"leg_1", &test.recipient, 300)]);
+
+    assert!(test.contract.try_create_swap_group(&group_id, &test.sender, &test.hashlock, &12350, &legs).is_err());
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+fn test_fill_order_requires_attestation() {
+    let test = HTLCTest::setup();
+    let order_id = Bytes::from_slice(&test.env, b"order_001");
+    let resolver = Address::generate(&test.env);
+    TokenAdminClient::new(&test.env, &test.token.address).mint(&resolver, &100);
+    bond_resolver(&test, &resolver, 100);
+    post_test_order(&test, &order_id);
+
+    test.govern(&Operation::SetAttestationCommittee(TestCommittee::new().committee(&test.env)));
+    assert!(test.contract.try_fill_order(&order_id, &resolver, &test.swap_id).is_err());
+    assert!(!test.contract.swap_exists(&test.swap_id));
+
+    // The maker can still take the escrow back
+    test.contract.cancel_order(&order_id);
+    assert_eq!(test.token.balance(&test.sender), 1000);
+}
+
+#[test]
+#[should_panic(expected = "EVM funding attestation required")]
+fn test_post_order_requires_attestation() {
+    let test = HTLCTest::setup();
+    test.govern(&Operation::SetAttestationCommittee(TestCommittee::new().committee(&test.env)));
+
+    post_test_order(&test, &Bytes::from_slice(&test.env, b"order_001"));
+}
+
+#[test]
+fn test_remove_attestation_committee() {
+    let test = HTLCTest::setup();
+    test.govern(&Operation::SetAttestationCommittee(TestCommittee::new().committee(&test.env)));
+    test.govern(&Operation::RemoveAttestationCommittee);
+    assert_eq!(test.contract.get_attestation_committee(), None);
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+}
+
+#[test]
+#[should_panic(expected = "not enough attestations")]
+fn test_attested_swap_below_threshold() {
+    let test = HTLCTest::setup();
+    let committee = TestCommittee::new();
+    test.enable_attestation(&committee);
+
+    let funding = evm_funding(&test);
+    let attestations = test.attest_funding(&committee, &funding, &[0]);
+    test.create_attested_swap(&test.swap_id, 12350, &funding, &attestations);
+}
+
+#[test]
+fn test_attested_swap_rejects_forged_funding() {
+    let test = HTLCTest::setup();
+    let committee = TestCommittee::new();
+    test.enable_attestation(&committee);
+
+    // Signatures over different fields don't carry over
+    let funding = evm_funding(&test);
+    let attestations = test.attest_funding(&committee, &funding, &[0, 2]);
+    let mut forged = funding.clone();
+    forged.amount = U256::from_u32(&test.env, 1);
+    assert!(test
+        .contract
+        .try_create_attested_swap(
+            &test.swap_id,
+            &test.sender,
+            &test.recipient,
+            &test.token.address,
+            &800,
+            &test.hashlock,
+            &12350,
+            &SwapOptions::none(&test.env),
+            &forged,
+            &attestations,
+        )
+        .is_err());
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+fn test_attestations_bound_to_swap() {
+    let test = HTLCTest::setup();
+    let committee = TestCommittee::new();
+    test.enable_attestation(&committee);
+    let funding = evm_funding(&test);
+    let attestations = test.attest_funding(&committee, &funding, &[0, 1]);
+
+    // Someone who saw the attestations can't open a swap to themselves with them
+    let attacker = Address::generate(&test.env);
+    TokenAdminClient::new(&test.env, &test.token.address).mint(&attacker, &800);
+    let attacker_swap_id = Bytes::from_slice(&test.env, b"attacker_swap");
+    for (swap_id, sender, recipient) in [
+        (&attacker_swap_id, &attacker, &attacker),
+        (&test.swap_id, &attacker, &test.recipient),
+        (&test.swap_id, &test.sender, &attacker),
+        (&attacker_swap_id, &test.sender, &test.recipient),
+    ] {
+        assert!(test
+            .contract
+            .try_create_attested_swap(
+                swap_id,
+                sender,
+                recipient,
+                &test.token.address,
+                &800,
+                &test.hashlock,
+                &12350,
+                &SwapOptions::none(&test.env),
+                &funding,
+                &attestations,
+            )
+            .is_err());
+    }
+
+    // The funding is still available to the swap the committee named
+    test.create_attested_swap(&test.swap_id, 12350, &funding, &attestations);
+    assert_eq!(test.contract.get_swap_funding(&test.swap_id), Some(funding));
+}
+
+#[test]
+#[should_panic(expected = "timelock must end before the EVM timelock")]
+fn test_attested_swap_outlives_evm_swap() {
+    let test = HTLCTest::setup();
+    let committee = TestCommittee::new();
+    test.enable_attestation(&committee);
+
+    let funding = evm_funding(&test);
+    let attestations = test.attest_funding(&committee, &funding, &[0, 1]);
+    test.create_attested_swap(&test.swap_id, 20000, &funding, &attestations);
+}
+
+#[test]
+#[should_panic(expected = "hashlock does not match EVM swap")]
+fn test_attested_swap_hashlock_mismatch() {
+    let test = HTLCTest::setup();
+    let committee = TestCommittee::new();
+    test.enable_attestation(&committee);
+
+    let mut funding = evm_funding(&test);
+    funding.hashlock = BytesN::from_array(&test.env, &[0xcc; 32]);
+    let attestations = test.attest_funding(&committee, &funding, &[0, 1]);
+    test.create_attested_swap(&test.swap_id, 12350, &funding, &attestations);
+}
+
+#[test]
+#[should_panic(expected = "EVM funding does not cover amount")]
+fn test_attested_swap_exceeds_evm_amount() {
+    let test = HTLCTest::setup();
+    let committee = TestCommittee::new();
+    test.enable_attestation(&committee);
+
+    // A genuine attestation of a smaller EVM funding can't back a larger lock
+    let mut funding = evm_funding(&test);
+    funding.amount = U256::from_u32(&test.env, 799);
+    let attestations = test.attest_funding(&committee, &funding, &[0, 1]);
+    test.create_attested_swap(&test.swap_id, 12350, &funding, &attestations);
+}
+
+#[test]
+fn test_attested_swap_scales_evm_decimals() {
+    let test = HTLCTest::setup();
+    let committee = TestCommittee::new();
+    test.enable_attestation(&committee);
+    test.govern(&Operation::SetFundingAsset(
+        11155111,
+        BytesN::from_array(&test.env, &[0xaa; 20]),
+        FundingAsset { token: test.token.address.clone(), evm_decimals: 18 },
+    ));
+
+    // 800 units at 7 decimals are 800e11 units at 18 decimals
+    let mut funding = evm_funding(&test);
+    funding.amount = U256::from_u128(&test.env, 800 * 10u128.pow(11) - 1);
+    let attestations = test.attest_funding(&committee, &funding, &[0, 1]);
+    assert!(test
+        .contract
+        .try_create_attested_swap(
+            &test.swap_id,
+            &test.sender,
+            &test.recipient,
+            &test.token.address,
+            &800,
+            &test.hashlock,
+            &12350,
+            &SwapOptions::none(&test.env),
+            &funding,
+            &attestations,
+        )
+        .is_err());
+
+    funding.amount = U256::from_u128(&test.env, 800 * 10u128.pow(11));
+    let attestations = test.attest_funding(&committee, &funding, &[0, 1]);
+    test.create_attested_swap(&test.swap_id, 12350, &funding, &attestations);
+    assert_eq!(test.token.balance(&test.contract.address), 800);
+}
+
+#[test]
+#[should_panic(expected = "EVM contract not registered")]
+fn test_attested_swap_unregistered_contract() {
+    let test = HTLCTest::setup();
+    let committee = TestCommittee::new();
+    test.enable_attestation(&committee);
+
+    let mut funding = evm_funding(&test);
+    funding.contract = BytesN::from_array(&test.env, &[0xdd; 20]);
+    let attestations = test.attest_funding(&committee, &funding, &[0, 1]);
+    test.create_attested_swap(&test.swap_id, 12350, &funding, &attestations);
+}
+
+#[test]
+fn test_attested_swap_stays_within_funding() {
+    let test = HTLCTest::setup();
+    let committee = TestCommittee::new();
+    test.enable_attestation(&committee);
+
+    let funding = evm_funding(&test);
+    let attestations = test.attest_funding(&committee, &funding, &[0, 1]);
+    test.create_attested_swap(&test.swap_id, 12350, &funding, &attestations);
+
+    // The EVM timelock is 20000 and the EVM amount 800
+    assert!(test.contract.try_extend_timelock(&test.swap_id, &20000).is_err());
+    assert!(test.contract.try_top_up(&test.swap_id, &1).is_err());
+
+    test.contract.extend_timelock(&test.swap_id, &19999);
+    assert_eq!(test.contract.get_swap(&test.swap_id).unwrap().timelock, 19999);
+    assert_eq!(test.contract.get_swap(&test.swap_id).unwrap().amount, 800);
+}
+
+#[test]
+fn test_initial_admin_holds_every_role() {
+    let test = HTLCTest::setup();
+
+    for role in [Role::Owner, Role::Pauser, Role::FeeManager, Role::TokenManager, Role::Upgrader] {
+        assert!(test.contract.has_role(&role, &test.admin));
+        assert!(!test.contract.has_role(&role, &test.sender));
+    }
+}
+
+#[test]
+fn test_owner_entrypoints_require_owner() {
+    let test = HTLCTest::setup();
+    let account = Address::generate(&test.env);
+
+    test.contract.grant_role(&Role::Pauser, &account);
+    assert_eq!(test.env.auths()[0].0, test.admin);
+    test.contract.revoke_role(&Role::Pauser, &account);
+    assert_eq!(test.env.auths()[0].0, test.admin);
+    test.contract.propose_owner(&account);
+    assert_eq!(test.env.auths()[0].0, test.admin);
+
+    test.env.set_auths(&[]);
+    assert!(test.contract.try_grant_role(&Role::Pauser, &account).is_err());
+    assert!(test.contract.try_revoke_role(&Role::Pauser, &test.admin).is_err());
+    assert!(test.contract.try_propose_owner(&test.sender).is_err());
+    assert!(test.contract.try_slash(&test.swap_id, &1).is_err());
+}
+
+#[test]
+fn test_role_entrypoints_require_role() {
+    let test = HTLCTest::setup();
+    let manager = Address::generate(&test.env);
+    let treasury = Address::generate(&test.env);
+
+    // Authorized but without the role
+    assert!(test.contract.try_set_bond_token(&manager, &test.token.address).is_err());
+    assert!(test.contract.try_skim(&manager, &test.token.address, &treasury).is_err());
+    assert!(test.contract.try_pause(&manager).is_err());
+    assert!(test.contract.try_unpause(&manager).is_err());
+
+    for role in [Role::Pauser, Role::FeeManager, Role::TokenManager] {
+        test.contract.grant_role(&role, &manager);
+    }
+    TokenAdminClient::new(&test.env, &test.token.address).mint(&test.contract.address, &5);
+
+    test.contract.set_bond_token(&manager, &test.token.address);
+    assert_eq!(test.env.auths()[0].0, manager);
+    assert_eq!(test.contract.skim(&manager, &test.token.address, &treasury), 5);
+    assert_eq!(test.env.auths()[0].0, manager);
+    test.contract.pause(&manager);
+    assert_eq!(test.env.auths()[0].0, manager);
+    test.contract.unpause(&manager);
+    assert_eq!(test.env.auths()[0].0, manager);
+
+    // Holding the role isn't enough without the holder's signature
+    test.env.set_auths(&[]);
+    assert!(test.contract.try_pause(&manager).is_err());
+}
+
+#[test]
+fn test_schedule_operation_requires_role() {
+    let test = HTLCTest::setup();
+    let manager = Address::generate(&test.env);
+    let operations = [
+        (Role::FeeManager, Operation::SetMaxIntegratorFeeBps(100)),
+        (Role::TokenManager, Operation::DelistToken(test.token.address.clone())),
+        (Role::TokenManager, Operation::RelistToken(test.token.address.clone())),
+        (Role::Upgrader, Operation::Upgrade(BytesN::from_array(&test.env, &[0; 32]))),
+        (Role::Owner, Operation::SetMinDelay(60)),
+        (Role::Owner, Operation::SetMaxTimelockHorizon(3600)),
+        (Role::Owner, Operation::SetRouter(manager.clone())),
+        (Role::Owner, Operation::SetMinBond(10)),
+        (Role::Owner, Operation::SetUnbondDelay(60)),
+        (Role::Owner, Operation::SetAttestationCommittee(TestCommittee::new().committee(&test.env))),
+        (Role::Owner, Operation::RemoveAttestationCommittee),
+        (
+            Role::Owner,
+            Operation::SetFundingAsset(
+                1,
+                BytesN::from_array(&test.env, &[0xaa; 20]),
+                FundingAsset { token: test.token.address.clone(), evm_decimals: 18 },
+            ),
+        ),
+    ];
+
+    for (_, operation) in operations.iter() {
+        assert!(test.contract.try_schedule_operation(&manager, operation).is_err());
+    }
+
+    for role in [Role::FeeManager, Role::TokenManager, Role::Upgrader] {
+        test.contract.grant_role(&role, &manager);
+    }
+    for (role, operation) in operations.iter() {
+        if *role == Role::Owner {
+            assert!(test.contract.try_schedule_operation(&manager, operation).is_err());
+        } else {
+            test.contract.schedule_operation(&manager, operation);
+            assert_eq!(test.env.auths()[0].0, manager);
+        }
+    }
+    test.contract.schedule_operation(&test.admin, &Operation::SetMinDelay(60));
+    assert_eq!(test.contract.get_pending_operations().len(), 5);
+}
+
+#[test]
+fn test_grant_and_revoke_role() {
+    let test = HTLCTest::setup();
+    let pauser = Address::generate(&test.env);
+
+    test.contract.grant_role(&Role::Pauser, &pauser);
+    let (topics, data) = test.last_htlc_event();
+    assert_eq!(Symbol::from_val(&test.env, &topics.get(0).unwrap()), events::ROLE_GRANTED);
+    assert_eq!(Address::from_val(&test.env, &topics.get(2).unwrap()), pauser);
+    assert_eq!(RoleChanged::from_val(&test.env, &data), RoleChanged { seq: 1, role: Role::Pauser });
+    assert!(test.contract.has_role(&Role::Pauser, &pauser));
+    assert!(!test.contract.has_role(&Role::Upgrader, &pauser));
+
+    // Granting a held role changes nothing
+    test.contract.grant_role(&Role::Pauser, &pauser);
+    assert_eq!(test.contract.get_event_seq(), 1);
+
+    test.contract.revoke_role(&Role::Pauser, &pauser);
+    let (topics, data) = test.last_htlc_event();
+    assert_eq!(Symbol::from_val(&test.env, &topics.get(0).unwrap()), events::ROLE_REVOKED);
+    assert_eq!(RoleChanged::from_val(&test.env, &data), RoleChanged { seq: 2, role: Role::Pauser });
+    assert!(!test.contract.has_role(&Role::Pauser, &pauser));
+    assert!(test.contract.try_pause(&pauser).is_err());
+}
+
+#[test]
+#[should_panic(expected = "ownership is transferred with propose_owner")]
+fn test_grant_owner_role() {
+    let test = HTLCTest::setup();
+
+    test.contract.grant_role(&Role::Owner, &test.sender);
+}
+
+#[test]
+fn test_two_step_ownership_transfer() {
+    let test = HTLCTest::setup();
+    let new_owner = Address::generate(&test.env);
+
+    test.contract.propose_owner(&new_owner);
+    let (topics, data) = test.last_htlc_event();
+    assert_eq!(Symbol::from_val(&test.env, &topics.get(0).unwrap()), events::OWNERSHIP_PROPOSED);
+    assert_eq!(Address::from_val(&test.env, &topics.get(2).unwrap()), new_owner);
+    assert_eq!(
+        OwnershipProposed::from_val(&test.env, &data),
+        OwnershipProposed { seq: 1, owner: test.admin.clone() }
+    );
+
+    // Nothing changes until the proposed owner accepts
+    assert_eq!(test.contract.get_admin(), test.admin);
+    assert_eq!(test.contract.get_pending_owner(), Some(new_owner.clone()));
+
+    test.contract.accept_ownership();
+    assert_eq!(test.env.auths()[0].0, new_owner);
+    // Each of the four other roles is revoked from the previous owner and granted
+    let (_, data) = test.last_htlc_event();
+    assert_eq!(
+        OwnershipTransferred::from_val(&test.env, &data),
+        OwnershipTransferred { seq: 10, previous_owner: test.admin.clone() }
+    );
+
+    assert_eq!(test.contract.get_admin(), new_owner);
+    assert_eq!(test.contract.get_pending_owner(), None);
+    for role in [Role::Owner, Role::Pauser, Role::FeeManager, Role::TokenManager, Role::Upgrader] {
+        assert!(test.contract.has_role(&role, &new_owner));
+        assert!(!test.contract.has_role(&role, &test.admin));
+    }
+    assert!(test.contract.try_pause(&test.admin).is_err());
+
+    test.contract.schedule_operation(&new_owner, &Operation::SetMinDelay(60));
+    assert_eq!(test.env.auths()[0].0, new_owner);
+    assert!(test.contract.try_schedule_operation(&test.admin, &Operation::SetMinDelay(60)).is_err());
+}
+
+#[test]
+#[should_panic(expected = "no pending owner")]
+fn test_accept_ownership_without_proposal() {
+    let test = HTLCTest::setup();
+
+    test.contract.accept_ownership();
+}
+
+#[test]
+fn test_pause_blocks_new_swaps_only() {
+    let test = HTLCTest::setup();
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &400,
+        &test.hashlock,
+        &12350,
+    );
+    test.contract.pause(&test.admin);
+    let (topics, _) = test.last_htlc_event();
+    assert_eq!(Symbol::from_val(&test.env, &topics.get(0).unwrap()), events::PAUSED);
+    assert!(test.contract.is_paused());
+
+    let swap_id_2 = Bytes::from_slice(&test.env, b"test_swap_002");
+    assert!(test
+        .contract
+        .try_create_swap(
+            &swap_id_2,
+            &test.sender,
+            &test.recipient,
+            &test.token.address,
+            &400,
+            &test.hashlock,
+            &12350,
+        )
+        .is_err());
+
+    // Existing swaps can still be settled
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+    assert_eq!(test.token.balance(&test.recipient), 400);
+
+    test.contract.unpause(&test.admin);
+    assert!(!test.contract.is_paused());
+    test.contract.create_swap(
+        &swap_id_2,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &400,
+        &test.hashlock,
+        &12350,
+    );
+}
+
+#[test]
+fn test_operation_waits_for_delay() {
+    let test = HTLCTest::setup();
+
+    let id = test.contract.schedule_operation(&test.admin, &Operation::SetMaxIntegratorFeeBps(100));
+    let (topics, data) = test.last_htlc_event();
+    assert_eq!(Symbol::from_val(&test.env, &topics.get(0).unwrap()), events::OPERATION_SCHEDULED);
+    assert_eq!(u32::from_val(&test.env, &topics.get(2).unwrap()), id);
+    assert_eq!(
+        OperationScheduled::from_val(&test.env, &data),
+        OperationScheduled {
+            seq: 1,
+            operation: Operation::SetMaxIntegratorFeeBps(100),
+            proposer: test.admin.clone(),
+            ready_at: 12345 + DEFAULT_MIN_DELAY,
+        }
+    );
+
+    assert_eq!(test.contract.get_pending_operations(), vec![&test.env, id]);
+    assert_eq!(
+        test.contract.get_operation(&id).unwrap().operation,
+        Operation::SetMaxIntegratorFeeBps(100)
+    );
+
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12345 + DEFAULT_MIN_DELAY - 1;
+    });
+    assert!(test.contract.try_execute_operation(&id).is_err());
+
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12345 + DEFAULT_MIN_DELAY;
+    });
+    // Anyone can execute a ready operation
+    test.env.set_auths(&[]);
+    test.contract.execute_operation(&id);
+    let (topics, data) = test.last_htlc_event();
+    assert_eq!(Symbol::from_val(&test.env, &topics.get(0).unwrap()), events::OPERATION_EXECUTED);
+    assert_eq!(
+        OperationExecuted::from_val(&test.env, &data),
+        OperationExecuted { seq: 2, operation: Operation::SetMaxIntegratorFeeBps(100) }
+    );
+
+    assert_eq!(test.contract.get_max_integrator_fee_bps(), 100);
+    assert_eq!(test.contract.get_operation(&id), None);
+    assert!(test.contract.get_pending_operations().is_empty());
+    assert!(test.contract.try_execute_operation(&id).is_err());
+}
+
+#[test]
+fn test_cancel_operation() {
+    let test = HTLCTest::setup();
+    let manager = Address::generate(&test.env);
+    let outsider = Address::generate(&test.env);
+    test.contract.grant_role(&Role::FeeManager, &manager);
+
+    let first = test.contract.schedule_operation(&manager, &Operation::SetMaxIntegratorFeeBps(100));
+    let second = test.contract.schedule_operation(&manager, &Operation::SetMaxIntegratorFeeBps(200));
+
+    assert!(test.contract.try_cancel_operation(&outsider, &first).is_err());
+
+    // The owner can cancel operations proposed by any role
+    test.contract.cancel_operation(&test.admin, &first);
+    let (topics, data) = test.last_htlc_event();
+    assert_eq!(Symbol::from_val(&test.env, &topics.get(0).unwrap()), events::OPERATION_CANCELLED);
+    assert_eq!(
+        OperationCancelled::from_val(&test.env, &data),
+        OperationCancelled { seq: 4, by: test.admin.clone() }
+    );
+    assert_eq!(test.contract.get_pending_operations(), vec![&test.env, second]);
+
+    test.contract.cancel_operation(&manager, &second);
+    assert_eq!(test.env.auths()[0].0, manager);
+    assert!(test.contract.get_pending_operations().is_empty());
+
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12345 + DEFAULT_MIN_DELAY;
+    });
+    assert!(test.contract.try_execute_operation(&first).is_err());
+    assert_eq!(test.contract.get_max_integrator_fee_bps(), 0);
+}
+
+#[test]
+fn test_delist_token() {
+    let test = HTLCTest::setup();
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &400,
+        &test.hashlock,
+        &12350,
+    );
+    test.govern(&Operation::DelistToken(test.token.address.clone()));
+    assert!(test.contract.is_token_delisted(&test.token.address));
+
+    let swap_id_2 = Bytes::from_slice(&test.env, b"test_swap_002");
+    assert!(test
+        .contract
+        .try_create_swap(
+            &swap_id_2,
+            &test.sender,
+            &test.recipient,
+            &test.token.address,
+            &400,
+            &test.hashlock,
+            &12350,
+        )
+        .is_err());
+
+    // Open swaps in a delisted token still settle
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+    assert_eq!(test.token.balance(&test.recipient), 400);
+
+    test.govern(&Operation::RelistToken(test.token.address.clone()));
+    assert!(!test.contract.is_token_delisted(&test.token.address));
+}
+
+#[test]
+#[should_panic(expected = "delay must be positive")]
+fn test_schedule_zero_min_delay() {
+    let test = HTLCTest::setup();
+
+    test.contract.schedule_operation(&test.admin, &Operation::SetMinDelay(0));
+}
+
+#[test]
+#[should_panic(expected = "delay exceeds maximum")]
+fn test_schedule_min_delay_above_cap() {
+    let test = HTLCTest::setup();
+
+    test.contract.schedule_operation(&test.admin, &Operation::SetMinDelay(MAX_DELAY + 1));
+}
+
+#[test]
+#[should_panic(expected = "horizon exceeds maximum")]
+fn test_schedule_timelock_horizon_above_cap() {
+    let test = HTLCTest::setup();
+
+    test.contract.schedule_operation(&test.admin, &Operation::SetMaxTimelockHorizon(MAX_DELAY + 1));
+}
+
+#[test]
+#[should_panic(expected = "unbond delay exceeds maximum")]
+fn test_schedule_unbond_delay_above_cap() {
+    let test = HTLCTest::setup();
+
+    test.contract.schedule_operation(&test.admin, &Operation::SetUnbondDelay(MAX_DELAY + 1));
+}
+
+#[test]
+fn test_delays_at_cap() {
+    let test = HTLCTest::setup();
+
+    test.govern(&Operation::SetMaxTimelockHorizon(MAX_DELAY));
+    test.govern(&Operation::SetUnbondDelay(MAX_DELAY));
+    test.govern(&Operation::SetMinDelay(MAX_DELAY));
+    assert_eq!(test.contract.get_min_delay(), MAX_DELAY);
+
+    // Governance keeps working at the longest delay
+    test.govern(&Operation::SetMinDelay(60));
+    assert_eq!(test.contract.get_min_delay(), 60);
+}
+
+#[test]
+fn test_unbond_delay_and_committee_changes_are_queued() {
+    let test = HTLCTest::setup();
+    test.govern(&Operation::SetAttestationCommittee(TestCommittee::new().committee(&test.env)));
+
+    // Neither frees bonds nor drops the funding check before the delay
+    test.contract.schedule_operation(&test.admin, &Operation::SetUnbondDelay(0));
+    test.contract.schedule_operation(&test.admin, &Operation::RemoveAttestationCommittee);
+    assert_eq!(test.contract.get_unbond_delay(), DEFAULT_UNBOND_DELAY);
+    assert!(test.contract.get_attestation_committee().is_some());
+
+    test.govern(&Operation::SetUnbondDelay(0));
+    test.govern(&Operation::RemoveAttestationCommittee);
+    assert_eq!(test.contract.get_unbond_delay(), 0);
+    assert_eq!(test.contract.get_attestation_committee(), None);
+}
+
+#[test]
+fn test_min_delay_change_is_queued() {
+    let test = HTLCTest::setup();
+
+    test.govern(&Operation::SetMinDelay(3600));
+    assert_eq!(test.contract.get_min_delay(), 3600);
+
+    let id = test.contract.schedule_operation(&test.admin, &Operation::SetMaxIntegratorFeeBps(100));
+    assert_eq!(test.contract.get_operation(&id).unwrap().ready_at, 12345 + 3600);
+}
+
+#[test]
+#[should_panic(expected = "fee cannot exceed 10000 bps")]
+fn test_schedule_invalid_fee_cap() {
+    let test = HTLCTest::setup();
+
+    test.contract.schedule_operation(&test.admin, &Operation::SetMaxIntegratorFeeBps(10_001));
+}
+
+#[test]
+fn test_deny_and_allow_events() {
+    let test = HTLCTest::setup();
+
+    test.contract.deny(&test.sender);
+    assert_eq!(test.env.auths()[0].0, test.admin);
+    let (topics, data) = test.last_htlc_event();
+    assert_eq!(Symbol::from_val(&test.env, &topics.get(0).unwrap()), events::ADDRESS_DENIED);
+    assert_eq!(Address::from_val(&test.env, &topics.get(2).unwrap()), test.sender);
+    assert_eq!(DenylistChanged::from_val(&test.env, &data), DenylistChanged { seq: 1 });
+    assert!(test.contract.is_denied(&test.sender));
+
+    test.contract.allow(&test.sender);
+    assert_eq!(test.env.auths()[0].0, test.admin);
+    let (topics, _) = test.last_htlc_event();
+    assert_eq!(Symbol::from_val(&test.env, &topics.get(0).unwrap()), events::ADDRESS_ALLOWED);
+    assert!(!test.contract.is_denied(&test.sender));
+
+    test.env.set_auths(&[]);
+    assert!(test.contract.try_deny(&test.recipient).is_err());
+    assert!(test.contract.try_allow(&test.recipient).is_err());
+}
+
+#[test]
+#[should_panic(expected = "address denied")]
+fn test_denied_sender_cannot_create_swap() {
+    let test = HTLCTest::setup();
+    test.contract.deny(&test.sender);
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+}
+
+#[test]
+#[should_panic(expected = "address denied")]
+fn test_swap_to_denied_recipient() {
+    let test = HTLCTest::setup();
+    test.contract.deny(&test.recipient);
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+}
+
+#[test]
+fn test_denied_recipient_cannot_withdraw() {
+    let test = HTLCTest::setup();
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+    test.contract.deny(&test.recipient);
+
+    assert!(test.contract.try_withdraw(&test.swap_id, &test.recipient, &test.preimage).is_err());
+    test.contract.reveal(&test.preimage);
+    assert!(test.contract.try_withdraw_revealed(&test.swap_id).is_err());
+    assert_eq!(test.token.balance(&test.recipient), 0);
+}
+
+#[test]
+fn test_denied_sender_can_refund_after_expiry() {
+    let test = HTLCTest::setup();
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+    test.contract.deny(&test.sender);
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12350;
+    });
+
+    test.contract.refund(&test.swap_id, &test.sender);
+    assert_eq!(test.token.balance(&test.sender), 1000);
+}
+
+#[test]
+fn test_denied_sender_cannot_move_funds_before_expiry() {
+    let test = HTLCTest::setup();
+
+    test.contract.create_swap(
+        &test.swap_id,
+        &test.sender,
+        &test.recipient,
+        &test.token.address,
+        &800,
+        &test.hashlock,
+        &12350,
+    );
+    test.contract.deny(&test.sender);
+
+    assert!(test.contract.try_top_up(&test.swap_id, &100).is_err());
+    assert!(test.contract.try_cancel(&test.swap_id).is_err());
+    assert_eq!(test.token.balance(&test.sender), 200);
+    assert_eq!(test.token.balance(&test.contract.address), 800);
+}
+
+#[test]
+fn test_denied_payees_rejected_at_create() {
+    let test = HTLCTest::setup();
+    let payee = Address::generate(&test.env);
+    test.set_max_integrator_fee_bps(100);
+    test.contract.deny(&payee);
+
+    let options = [
+        payout_options(&test.env, &[(test.recipient.clone(), 5_000), (payee.clone(), 5_000)]),
+        integrator_options(&test.env, &payee, 50),
+        callback_options(&test.env, &payee, &Bytes::new(&test.env)),
+    ];
+    for options in options.iter() {
+        assert!(test
+            .contract
+            .try_create_swap_with_options(
+                &test.swap_id,
+                &test.sender,
+                &test.recipient,
+                &test.token.address,
+                &800,
+                &test.hashlock,
+                &12350,
+                options,
+            )
+            .is_err());
+    }
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+fn test_denied_payees_rejected_at_withdraw() {
+    let test = HTLCTest::setup();
+    let beneficiary = Address::generate(&test.env);
+    let integrator = Address::generate(&test.env);
+    test.set_max_integrator_fee_bps(100);
+
+    let mut options = payout_options(&test.env, &[(beneficiary.clone(), 10_000)]);
+    options.integrator = Some(integrator.clone());
+    options.integrator_fee_bps = 50;
+    test.create_swap_with_options(800, &options);
+
+    test.contract.deny(&beneficiary);
+    assert!(test.contract.try_withdraw(&test.swap_id, &test.recipient, &test.preimage).is_err());
+
+    test.contract.allow(&beneficiary);
+    test.contract.deny(&integrator);
+    assert!(test.contract.try_withdraw(&test.swap_id, &test.recipient, &test.preimage).is_err());
+    assert_eq!(test.token.balance(&beneficiary), 0);
+
+    test.contract.allow(&integrator);
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+    assert_eq!(test.token.balance(&beneficiary), 796);
+}
+
+fn escrow_options(env: &Env, arbiter: &Address) -> SwapOptions {
+    let mut options = SwapOptions::none(env);
+    options.arbiter = Some(arbiter.clone());
+    options
+}
+
+#[test]
+fn test_arbiter_releases_to_recipient() {
+    let test = HTLCTest::setup();
+    let arbiter = Address::generate(&test.env);
+    test.create_swap_with_options(800, &escrow_options(&test.env, &arbiter));
+
+    test.contract.arbitrate(&test.swap_id, &true);
+    assert_eq!(test.env.auths().len(), 1);
+    assert_eq!(test.env.auths()[0].0, arbiter);
+
+    let (topics, data) = test.last_htlc_event();
+    assert_swap_topics(&test, &topics, events::SWAP_SETTLED, &test.swap_id);
+    assert_eq!(
+        SwapSettled::from_val(&test.env, &data),
+        SwapSettled {
+            seq: 2,
+            arbiter: Some(arbiter),
+            token: test.token.address.clone(),
+            recipient_amount: 800,
+            sender_amount: 0,
+        }
+    );
+
+    assert_eq!(test.token.balance(&test.recipient), 800);
+    assert_eq!(test.token.balance(&test.sender), 200);
+    assert_eq!(test.contract.get_locked_total(&test.token.address), 0);
+    assert_eq!(
+        test.contract.get_statuses(&vec![&test.env, test.swap_id.clone()]),
+        vec![&test.env, Some(SwapStatus::Settled)]
+    );
+    assert_eq!(test.contract.get_participant_stats(&test.recipient).settled, 1);
+}
+
+#[test]
+fn test_arbiter_returns_to_sender() {
+    let test = HTLCTest::setup();
+    let arbiter = Address::generate(&test.env);
+    test.create_swap_with_options(800, &escrow_options(&test.env, &arbiter));
+
+    test.contract.arbitrate(&test.swap_id, &false);
+
+    assert_eq!(test.token.balance(&test.sender), 1000);
+    assert_eq!(test.token.balance(&test.recipient), 0);
+    assert!(test.contract.get_swap(&test.swap_id).unwrap().is_settled);
+
+    // The swap is closed for every other path
+    assert!(test.contract.try_withdraw(&test.swap_id, &test.recipient, &test.preimage).is_err());
+    assert!(test.contract.try_arbitrate(&test.swap_id, &true).is_err());
+}
+
+#[test]
+#[should_panic(expected = "timelock expired")]
+fn test_arbitrate_after_expiry() {
+    let test = HTLCTest::setup();
+    let arbiter = Address::generate(&test.env);
+    test.create_swap_with_options(800, &escrow_options(&test.env, &arbiter));
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12350;
+    });
+
+    test.contract.arbitrate(&test.swap_id, &true);
+}
+
+#[test]
+#[should_panic(expected = "swap has no arbiter")]
+fn test_arbitrate_plain_swap() {
+    let test = HTLCTest::setup();
+    test.create_swap_with_options(800, &SwapOptions::none(&test.env));
+
+    test.contract.arbitrate(&test.swap_id, &true);
+}
+
+#[test]
+#[should_panic(expected = "arbiter must be a third party")]
+fn test_recipient_as_arbiter() {
+    let test = HTLCTest::setup();
+
+    test.create_swap_with_options(800, &escrow_options(&test.env, &test.recipient));
+}
+
+#[test]
+fn test_joint_settlement() {
+    let test = HTLCTest::setup();
+    let arbiter = Address::generate(&test.env);
+    test.create_swap_with_options(800, &escrow_options(&test.env, &arbiter));
+
+    assert!(test.contract.try_settle(&test.swap_id, &801).is_err());
+    test.contract.settle(&test.swap_id, &300);
+    let auths: std::vec::Vec<Address> = test.env.auths().into_iter().map(|(address, _)| address).collect();
+    assert_eq!(auths, [test.sender.clone(), test.recipient.clone()]);
+
+    let (_, data) = test.last_htlc_event();
+    let event = SwapSettled::from_val(&test.env, &data);
+    assert_eq!((event.arbiter, event.recipient_amount, event.sender_amount), (None, 300, 500));
+
+    assert_eq!(test.token.balance(&test.recipient), 300);
+    assert_eq!(test.token.balance(&test.sender), 700);
+    assert_eq!(test.contract.get_locked_total(&test.token.address), 0);
+}
+
+#[test]
+fn test_joint_settlement_after_expiry() {
+    let test = HTLCTest::setup();
+    let arbiter = Address::generate(&test.env);
+    test.create_swap_with_options(800, &escrow_options(&test.env, &arbiter));
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12350;
+    });
+
+    test.contract.settle(&test.swap_id, &800);
+    assert_eq!(test.token.balance(&test.recipient), 800);
+    assert!(test.contract.try_refund(&test.swap_id, &test.sender).is_err());
+}
+
+/// 2-of-3 approver set
+fn approval_options(env: &Env) -> (SwapOptions, [Address; 3]) {
+    let approvers = [Address::generate(env), Address::generate(env), Address::generate(env)];
+    let mut options = SwapOptions::none(env);
+    options.approvers = vec![env, approvers[0].clone(), approvers[1].clone(), approvers[2].clone()];
+    options.approval_threshold = 2;
+    (options, approvers)
+}
+
+#[test]
+fn test_withdraw_with_recorded_approvals() {
+    let test = HTLCTest::setup();
+    let (options, approvers) = approval_options(&test.env);
+    test.create_swap_with_options(800, &options);
+
+    test.contract.approve(&test.swap_id, &approvers[0]);
+    assert_eq!(test.env.auths()[0].0, approvers[0]);
+    let (topics, data) = test.last_htlc_event();
+    assert_swap_topics(&test, &topics, events::SWAP_APPROVED, &test.swap_id);
+    assert_eq!(
+        SwapApproved::from_val(&test.env, &data),
+        SwapApproved { seq: 2, approver: approvers[0].clone(), approvals: 1 }
+    );
+
+    // Approving again doesn't count twice
+    test.contract.approve(&test.swap_id, &approvers[0]);
+    let no_signers = Vec::new(&test.env);
+    assert!(test.contract.try_withdraw_approved(&test.swap_id, &no_signers, &test.preimage).is_err());
+
+    test.contract.approve(&test.swap_id, &approvers[2]);
+    assert_eq!(
+        test.contract.get_approvals(&test.swap_id),
+        vec![&test.env, approvers[0].clone(), approvers[2].clone()]
+    );
+    test.contract.withdraw_approved(&test.swap_id, &no_signers, &test.preimage);
+
+    assert_eq!(test.token.balance(&test.recipient), 800);
+    assert!(test.contract.get_swap(&test.swap_id).unwrap().is_withdrawn);
+    assert!(test.contract.get_approvals(&test.swap_id).is_empty());
+}
+
+#[test]
+fn test_withdraw_signed_by_approvers() {
+    let test = HTLCTest::setup();
+    let (options, approvers) = approval_options(&test.env);
+    test.create_swap_with_options(800, &options);
+
+    // One recorded approval plus one signer of the withdraw itself
+    test.contract.approve(&test.swap_id, &approvers[1]);
+    test.contract.withdraw_approved(&test.swap_id, &vec![&test.env, approvers[2].clone()], &test.preimage);
+    assert_eq!(test.env.auths().len(), 1);
+    assert_eq!(test.env.auths()[0].0, approvers[2]);
+    assert_eq!(test.token.balance(&test.recipient), 800);
+}
+
+#[test]
+fn test_withdraw_approved_requires_preimage() {
+    let test = HTLCTest::setup();
+    let (options, approvers) = approval_options(&test.env);
+    test.create_swap_with_options(800, &options);
+    let signers = vec![&test.env, approvers[0].clone(), approvers[1].clone()];
+
+    let wrong_preimage = Bytes::from_slice(&test.env, &[9; 32]);
+    assert!(test.contract.try_withdraw_approved(&test.swap_id, &signers, &wrong_preimage).is_err());
+
+    let outsider = vec![&test.env, approvers[0].clone(), Address::generate(&test.env)];
+    assert!(test.contract.try_withdraw_approved(&test.swap_id, &outsider, &test.preimage).is_err());
+    assert_eq!(test.token.balance(&test.recipient), 0);
+}
+
+#[test]
+#[should_panic(expected = "swap requires approvals")]
+fn test_plain_withdraw_of_approval_swap() {
+    let test = HTLCTest::setup();
+    let (options, _) = approval_options(&test.env);
+    test.create_swap_with_options(800, &options);
+
+    test.contract.withdraw(&test.swap_id, &test.recipient, &test.preimage);
+}
+
+#[test]
+fn test_cancel_approval_swap_needs_threshold() {
+    let test = HTLCTest::setup();
+    let (options, approvers) = approval_options(&test.env);
+    test.create_swap_with_options(800, &options);
+
+    // The recipient alone can't undo the swap, nor can a release approval
+    assert!(test.contract.try_cancel(&test.swap_id).is_err());
+    test.contract.approve(&test.swap_id, &approvers[0]);
+    let one_signer = vec![&test.env, approvers[1].clone()];
+    assert!(test.contract.try_cancel_approved(&test.swap_id, &one_signer).is_err());
+
+    let signers = vec![&test.env, approvers[1].clone(), approvers[2].clone()];
+    test.contract.cancel_approved(&test.swap_id, &signers);
+    assert_eq!(test.env.auths().len(), 2);
+    assert_eq!(test.token.balance(&test.sender), 1000);
+    assert!(test.contract.get_swap(&test.swap_id).unwrap().is_cancelled);
+    assert!(test.contract.get_approvals(&test.swap_id).is_empty());
+}
+
+#[test]
+fn test_refund_clears_approvals() {
+    let test = HTLCTest::setup();
+    let (options, approvers) = approval_options(&test.env);
+    test.create_swap_with_options(800, &options);
+
+    test.contract.approve(&test.swap_id, &approvers[0]);
+    test.env.ledger().with_mut(|li| {
+        li.timestamp = 12350;
+    });
+    test.contract.refund(&test.swap_id, &test.sender);
+    assert!(test.contract.get_approvals(&test.swap_id).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "not an approver")]
+fn test_approve_by_outsider() {
+    let test = HTLCTest::setup();
+    let (options, _) = approval_options(&test.env);
+    test.create_swap_with_options(800, &options);
+
+    test.contract.approve(&test.swap_id, &test.recipient);
+}
+
+#[test]
+#[should_panic(expected = "escrow swaps cannot have approvers")]
+fn test_approvers_with_arbiter() {
+    let test = HTLCTest::setup();
+    let (mut options, _) = approval_options(&test.env);
+    options.arbiter = Some(Address::generate(&test.env));
+
+    test.create_swap_with_options(800, &options);
+}
+
+#[test]
+#[should_panic(expected = "invalid approval threshold")]
+fn test_approval_threshold_above_set() {
+    let test = HTLCTest::setup();
+    let (mut options, _) = approval_options(&test.env);
+    options.approval_threshold = 4;
+
+    test.create_swap_with_options(800, &options);
+}
+
+#[test]
+fn test_escrow_settlement_respects_denylist() {
+    let test = HTLCTest::setup();
+    let arbiter = Address::generate(&test.env);
+    test.create_swap_with_options(800, &escrow_options(&test.env, &arbiter));
+    test.contract.deny(&test.sender);
+
+    // Nothing may flow back to a denied sender before expiry
+    assert!(test.contract.try_arbitrate(&test.swap_id, &false).is_err());
+    assert!(test.contract.try_settle(&test.swap_id, &300).is_err());
+
+    // Paying the recipient in full is still possible
+    test.contract.arbitrate(&test.swap_id, &true);
+    assert_eq!(test.token.balance(&test.recipient), 800);
+}
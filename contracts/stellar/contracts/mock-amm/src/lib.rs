@@ -0,0 +1,111 @@
+#![no_std]
+
+//! Single-pair constant-product pool exposing a Soroswap-style router entrypoint.
+//! Used to test `withdraw_and_swap` in the HTLC contract; not meant for deployment.
+
+use soroban_sdk::{contract, contractimpl, contracttype, token, vec, Address, Env, Vec};
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    TokenA,
+    TokenB,
+    Reserves,
+}
+
+#[contract]
+pub struct MockAmm;
+
+fn tokens(env: &Env) -> (Address, Address) {
+    (
+        env.storage().instance().get(&DataKey::TokenA).unwrap(),
+        env.storage().instance().get(&DataKey::TokenB).unwrap(),
+    )
+}
+
+#[contractimpl]
+impl MockAmm {
+    pub fn __constructor(env: Env, token_a: Address, token_b: Address) {
+        env.storage().instance().set(&DataKey::TokenA, &token_a);
+        env.storage().instance().set(&DataKey::TokenB, &token_b);
+        env.storage().instance().set(&DataKey::Reserves, &(0i128, 0i128));
+    }
+
+    /// Deposit both tokens into the pool
+    pub fn add_liquidity(env: Env, from: Address, amount_a: i128, amount_b: i128) {
+        from.require_auth();
+
+        let (token_a, token_b) = tokens(&env);
+        let pool = env.current_contract_address();
+        token::Client::new(&env, &token_a).transfer(&from, &pool, &amount_a);
+        token::Client::new(&env, &token_b).transfer(&from, &pool, &amount_b);
+
+        let (reserve_a, reserve_b) = Self::get_reserves(env.clone());
+        env.storage()
+            .instance()
+            .set(&DataKey::Reserves, &(reserve_a + amount_a, reserve_b + amount_b));
+    }
+
+    pub fn get_reserves(env: Env) -> (i128, i128) {
+        env.storage().instance().get(&DataKey::Reserves).unwrap()
+    }
+
+    /// Output for `amount_in` with a 0.3% fee, as in Uniswap V2 / Soroswap
+    pub fn get_amount_out(amount_in: i128, reserve_in: i128, reserve_out: i128) -> i128 {
+        let amount_in_with_fee = amount_in * 997;
+        amount_in_with_fee * reserve_out / (reserve_in * 1000 + amount_in_with_fee)
+    }
+
+    pub fn swap_exact_tokens_for_tokens(
+        env: Env,
+        amount_in: i128,
+        amount_out_min: i128,
+        path: Vec<Address>,
+        to: Address,
+        deadline: u64,
+    ) -> Vec<i128> {
+        to.require_auth();
+
+        if env.ledger().timestamp() > deadline {
+            panic!("deadline expired");
+        }
+        if path.len() != 2 {
+            panic!("unsupported path");
+        }
+
+        let (token_a, token_b) = tokens(&env);
+        let (reserve_a, reserve_b) = Self::get_reserves(env.clone());
+        let token_in = path.get_unchecked(0);
+        let token_out = path.get_unchecked(1);
+        let a_to_b = if token_in == token_a && token_out == token_b {
+            true
+        } else if token_in == token_b && token_out == token_a {
+            false
+        } else {
+            panic!("unsupported path");
+        };
+
+        let (reserve_in, reserve_out) = if a_to_b {
+            (reserve_a, reserve_b)
+        } else {
+            (reserve_b, reserve_a)
+        };
+        let amount_out = Self::get_amount_out(amount_in, reserve_in, reserve_out);
+        if amount_out < amount_out_min {
+            panic!("insufficient output amount");
+        }
+
+        let pool = env.current_contract_address();
+        token::Client::new(&env, &token_in).transfer(&to, &pool, &amount_in);
+        token::Client::new(&env, &token_out).transfer(&pool, &to, &amount_out);
+
+        let reserves = if a_to_b {
+            (reserve_a + amount_in, reserve_b - amount_out)
+        } else {
+            (reserve_a - amount_out, reserve_b + amount_in)
+        };
+        env.storage().instance().set(&DataKey::Reserves, &reserves);
+
+        vec![&env, amount_in, amount_out]
+    }
+}
@@ -74,20 +74,20 @@ function simpleEventLog(events) {
     //   topics: event.topic.map((t) => scValToNative(t)),
     //   value: scValToNative(event.value),
     // };
-    // if topic[0] CREATED and topic[1] swap
+    // topics: [name, version, swap_id, hashlock]
     const topics = event.topic.map((t) => scValToNative(t));
     const value = scValToNative(event.value);
-    if (topics[0] === "CREATED" && topics[1] === "swap") {
-      return swapCreatedEventLog(value);
+    if (topics[0] === "CREATED") {
+      return swapCreatedEventLog(topics, value);
     }
-    if (topics[0] === "WITHDRAWN" && topics[1] === "withdraw") {
-      return swapWithdrawnEventLog(value);
+    if (topics[0] === "WITHDRAWN") {
+      return swapWithdrawnEventLog(topics, value);
     }
     return null;
   });
 }
 
-function swapCreatedEventLog(obj: any[]): {
+function swapCreatedEventLog(topics: any[], obj: any): {
   type: "created";
   swapId: string;
   sender: string;
@@ -99,18 +99,18 @@ function swapCreatedEventLog(obj: any[]): {
 } {
   return {
     type: "created",
-    swapId: Buffer.from(obj[0]).toString("hex"),
-    sender: obj[1],
-    recipient: obj[2],
-    token: obj[3],
-    amount: obj[4],
-    hashlock: Buffer.from(obj[5]).toString("hex"),
-    timelock: obj[6],
+    swapId: Buffer.from(topics[2]).toString("hex"),
+    sender: obj.sender,
+    recipient: obj.recipient,
+    token: obj.token,
+    amount: obj.amount,
+    hashlock: Buffer.from(topics[3]).toString("hex"),
+    timelock: obj.timelock,
   };
 }
 
-//(swap_id, updated_swap.sender, recipient, updated_swap.token, updated_swap.amount, preimage)
-function swapWithdrawnEventLog(obj: any[]): {
+// SwapWithdrawn { sender, recipient, token, amount, preimage }
+function swapWithdrawnEventLog(topics: any[], obj: any): {
   type: "withdrawn";
   swapId: string;
   sender: string;
@@ -121,12 +121,12 @@ function swapWithdrawnEventLog(obj: any[]): {
 } {
   return {
     type: "withdrawn",
-    swapId: Buffer.from(obj[0]).toString("hex"),
-    sender: obj[1],
-    recipient: obj[2],
-    token: obj[3],
-    amount: obj[4],
-    preimage: Buffer.from(obj[5]).toString("hex"),
+    swapId: Buffer.from(topics[2]).toString("hex"),
+    sender: obj.sender,
+    recipient: obj.recipient,
+    token: obj.token,
+    amount: obj.amount,
+    preimage: Buffer.from(obj.preimage).toString("hex"),
   };
 }
 